@@ -0,0 +1,151 @@
+use core::str::FromStr;
+
+use alloc::vec::Vec;
+
+use crate::{SpellId, swipe::Swipe, tap::Tap};
+
+/// a keyboard shortcut, as the modifier byte & key codes of a USB HID keyboard report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shortcut {
+    pub modifier: u8,
+    pub keycodes: [u8; 6],
+}
+
+/// GUI+Enter, run by spells that were never bound to anything else.
+pub const DEFAULT_SHORTCUT: Shortcut = Shortcut {
+    modifier: 0x08,
+    keycodes: [0x28, 0, 0, 0, 0, 0],
+};
+
+impl FromStr for Shortcut {
+    type Err = ();
+
+    /// parses keys joined by `+`, like "ctrl+alt+t". at most 6 keys that are not modifiers.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut shortcut = Self {
+            modifier: 0,
+            keycodes: [0; 6],
+        };
+        let mut n_keys = 0;
+
+        for key in s.split('+') {
+            if let Some(modifier) = modifier(key) {
+                shortcut.modifier |= modifier;
+            } else {
+                *shortcut.keycodes.get_mut(n_keys).ok_or(())? = keycode(key).ok_or(())?;
+                n_keys += 1;
+            }
+        }
+
+        if shortcut.modifier == 0 && n_keys == 0 {
+            return Err(());
+        }
+
+        Ok(shortcut)
+    }
+}
+
+fn modifier(key: &str) -> Option<u8> {
+    match key {
+        "ctrl" => Some(0x01),
+        "shift" => Some(0x02),
+        "alt" => Some(0x04),
+        "gui" | "super" | "meta" => Some(0x08),
+        _ => None,
+    }
+}
+
+/// the HID usage id of a key on the keyboard page.
+fn keycode(key: &str) -> Option<u8> {
+    let bytes = key.as_bytes();
+
+    match key {
+        _ if bytes.len() == 1 && bytes[0].is_ascii_lowercase() => Some(0x04 + bytes[0] - b'a'),
+        "0" => Some(0x27),
+        _ if bytes.len() == 1 && bytes[0].is_ascii_digit() => Some(0x1e + bytes[0] - b'1'),
+        "enter" => Some(0x28),
+        "esc" => Some(0x29),
+        "backspace" => Some(0x2a),
+        "tab" => Some(0x2b),
+        "space" => Some(0x2c),
+        "right" => Some(0x4f),
+        "left" => Some(0x50),
+        "down" => Some(0x51),
+        "up" => Some(0x52),
+        _ => match key.strip_prefix('f')?.parse::<u8>().ok()? {
+            n @ 1..=12 => Some(0x3a + n - 1),
+            _ => None,
+        },
+    }
+}
+
+/// what can be bound to a shortcut.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trigger {
+    Spell(SpellId),
+    /// a spell drawn with the finger resting at the end before lifting.
+    Held(SpellId),
+    Tap(Tap),
+    /// a swipe & how many fingers made it.
+    Swipe(Swipe, u8),
+}
+
+impl FromStr for Trigger {
+    type Err = ();
+
+    /// a spell id, a spell id held like "3-held", the name of a tap, or the name of a swipe with
+    /// an optional finger count like "2-finger-swipe-left".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(id) = s.parse() {
+            return Ok(Self::Spell(id));
+        }
+
+        if let Some(Ok(id)) = s.strip_suffix("-held").map(str::parse) {
+            return Ok(Self::Held(id));
+        }
+
+        if let Ok(tap) = s.parse() {
+            return Ok(Self::Tap(tap));
+        }
+
+        match s.split_once("-finger-") {
+            Some((fingers, swipe)) => Ok(Self::Swipe(
+                swipe.parse()?,
+                fingers.parse().map_err(|_| ())?,
+            )),
+            None => Ok(Self::Swipe(s.parse()?, 1)),
+        }
+    }
+}
+
+/// the shortcut each trigger runs.
+#[derive(Default)]
+pub struct Bindings {
+    bindings: Vec<(Trigger, Shortcut)>,
+}
+
+impl Bindings {
+    /// binds `trigger` to `shortcut`, replacing what it was bound to.
+    pub fn bind(&mut self, trigger: Trigger, shortcut: Shortcut) {
+        self.unbind(trigger);
+        self.bindings.push((trigger, shortcut));
+    }
+
+    pub fn unbind(&mut self, trigger: Trigger) {
+        self.bindings.retain(|(bound, _)| *bound != trigger);
+    }
+
+    /// true when any spell has something bound to holding it.
+    pub fn holds(&self) -> bool {
+        self.bindings
+            .iter()
+            .any(|(bound, _)| matches!(bound, Trigger::Held(_)))
+    }
+
+    pub fn get(&self, trigger: Trigger) -> Option<Shortcut> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == trigger)
+            .map(|(_, shortcut)| *shortcut)
+    }
+}
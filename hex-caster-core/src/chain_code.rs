@@ -0,0 +1,254 @@
+// https://dl.acm.org/doi/10.5555/2386301.2386308 (ShortStraw)
+
+use core::{cmp::Ordering, f32::consts::FRAC_PI_4};
+
+use alloc::vec::Vec;
+// unused when the tests pull in `std`, as in `spell_compare`.
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use num_traits::Float;
+
+use crate::{
+    Spell,
+    spell_compare::{MIN_POINTS, Matcher, RecognizeError, SpellOptions},
+};
+
+/// the directions of the straight segments of a spell, clockwise from right as the pad's y grows
+/// downwards (0 is right, 2 is down, 4 is left & 6 is up). a direction never repeats, two
+/// segments going the same way are one segment.
+pub type ChainCode = Vec<u8>;
+
+/// how many points the diagonal of the spell's bounding box is resampled to, so corners are found
+/// at the same scale however big the spell is drawn.
+const POINTS_PER_DIAGONAL: f32 = 40.0;
+/// how many points either side of a point its straw spans.
+const STRAW_WINDOW: usize = 3;
+/// a point whose straw is shorter than this times the median straw is part of a corner.
+const MEDIAN_RATIO: f32 = 0.95;
+/// how straight a run of points has to be to be a segment, as the distance between its ends over
+/// the length of its path.
+const LINE_RATIO: f32 = 0.95;
+
+/// ShortStraw's corner detection, matching chain codes by edit distance. polylines like "L", "Z"
+/// & arrows keep their sharp corners, where the other matchers resample them away, & a single
+/// example is usually enough. curves are cut into the straight segments they are closest to.
+pub struct ShortStraw;
+
+impl Matcher for ShortStraw {
+    type Template = ChainCode;
+
+    fn normalize(&self, spell: &Spell) -> Result<Self::Template, RecognizeError> {
+        chain_code(spell)
+    }
+
+    fn score(
+        &self,
+        cast_spell: &Self::Template,
+        template: &Self::Template,
+        options: &SpellOptions,
+    ) -> f32 {
+        // one step either way is the chain code's version of the `THETA` search.
+        let turns: &[u8] = if options.rotation_sensitive {
+            &[7, 0, 1]
+        } else {
+            &[0, 1, 2, 3, 4, 5, 6, 7]
+        };
+        let d = turns
+            .iter()
+            .map(|turn| edit_distance(cast_spell, &turned(template, *turn)))
+            .min_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .unwrap_or_default();
+
+        1.0 - d / cast_spell.len().max(template.len()).max(1) as f32
+    }
+}
+
+/// the chain code of the spell's corners, the strokes of a multistroke spell joined in order.
+pub fn chain_code(spell: &Spell) -> Result<ChainCode, RecognizeError> {
+    let points: Vec<(f32, f32)> = spell
+        .iter()
+        .flatten()
+        .map(|(x, y)| (*x as f32, *y as f32))
+        .collect();
+
+    if points.len() < MIN_POINTS {
+        return Err(RecognizeError::TooShort);
+    }
+
+    let (min, max) = points.iter().fold((points[0], points[0]), |(min, max), p| {
+        (
+            (min.0.min(p.0), min.1.min(p.1)),
+            (max.0.max(p.0), max.1.max(p.1)),
+        )
+    });
+    let diagonal = distance(min, max);
+
+    if diagonal == 0.0 {
+        return Err(RecognizeError::Degenerate);
+    }
+
+    let points = resample(&points, diagonal / POINTS_PER_DIAGONAL);
+    let mut code = ChainCode::new();
+
+    for segment in corners(&points).windows(2) {
+        let direction = direction(points[segment[0]], points[segment[1]]);
+
+        if code.last() != Some(&direction) {
+            code.push(direction);
+        }
+    }
+
+    Ok(code)
+}
+
+/// the indices of the corners of the evenly spaced `points`, including the first & last point.
+/// a point is a corner when its straw, the distance between the points `STRAW_WINDOW` either side
+/// of it, is shortest of the points around it & much shorter than usual.
+pub fn corners(points: &[(f32, f32)]) -> Vec<usize> {
+    let w = STRAW_WINDOW;
+    let n = points.len();
+    let straw = |i: usize| {
+        if i >= w && i + w < n {
+            distance(points[i - w], points[i + w])
+        } else {
+            f32::INFINITY
+        }
+    };
+    let mut corners = Vec::from([0]);
+
+    if n > 2 * w {
+        let mut straws: Vec<f32> = (w..n - w).map(straw).collect();
+        straws.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let threshold = straws[straws.len() / 2] * MEDIAN_RATIO;
+
+        let mut i = w;
+        while i < n - w {
+            if straw(i) < threshold {
+                let mut corner = i;
+
+                while i < n - w && straw(i) < threshold {
+                    if straw(i) < straw(corner) {
+                        corner = i;
+                    }
+                    i += 1;
+                }
+
+                corners.push(corner);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    corners.push(n - 1);
+
+    // segments that are not straight are missing a corner, it is at the shortest straw of their
+    // middle half.
+    let mut i = 1;
+    while i < corners.len() {
+        let (a, b) = (corners[i - 1], corners[i]);
+
+        if b - a > 1 && !is_line(points, a, b) {
+            let quarter = (b - a) / 4;
+            let corner = (a + quarter.max(1)..=b - quarter.max(1))
+                .min_by(|x, y| straw(*x).partial_cmp(&straw(*y)).unwrap_or(Ordering::Equal))
+                .filter(|corner| straw(*corner).is_finite())
+                .unwrap_or((a + b) / 2);
+
+            corners.insert(i, corner);
+        } else {
+            i += 1;
+        }
+    }
+
+    // & corners in the middle of a straight line are not corners.
+    let mut i = 1;
+    while i + 1 < corners.len() {
+        if is_line(points, corners[i - 1], corners[i + 1]) {
+            corners.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    corners
+}
+
+/// the levenshtein distance between two chain codes, where swapping a direction for another
+/// costs how far it turns, from 0.25 for 45 degrees to 1 for a u-turn.
+pub fn edit_distance(a: &[u8], b: &[u8]) -> f32 {
+    let mut row: Vec<f32> = (0..=b.len()).map(|j| j as f32).collect();
+
+    for (i, x) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = (i + 1) as f32;
+
+        for (j, y) in b.iter().enumerate() {
+            let d = (row[j + 1] + 1.0)
+                .min(row[j] + 1.0)
+                .min(diagonal + turn_cost(*x, *y));
+            diagonal = row[j + 1];
+            row[j + 1] = d;
+        }
+    }
+
+    row[b.len()]
+}
+
+fn turn_cost(a: u8, b: u8) -> f32 {
+    let d = (a as i32 - b as i32).rem_euclid(8);
+
+    d.min(8 - d) as f32 / 4.0
+}
+
+/// the chain code turned clockwise by `turn` steps of 45 degrees.
+fn turned(code: &[u8], turn: u8) -> ChainCode {
+    code.iter().map(|d| (d + turn) % 8).collect()
+}
+
+/// points spaced `spacing` apart along the path, like $1's resampling but keeping as many points
+/// as fit.
+fn resample(points: &[(f32, f32)], spacing: f32) -> Vec<(f32, f32)> {
+    let mut resampled = Vec::from([points[0]]);
+    let mut d = 0.0;
+
+    for pair in points.windows(2) {
+        let (mut a, b) = (pair[0], pair[1]);
+        let mut step = distance(a, b);
+
+        while step > 0.0 && d + step >= spacing {
+            let t = (spacing - d) / step;
+            a = (a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1));
+            resampled.push(a);
+            step = distance(a, b);
+            d = 0.0;
+        }
+
+        d += step;
+    }
+
+    if d > 0.0 {
+        resampled.push(points[points.len() - 1]);
+    }
+
+    resampled
+}
+
+fn is_line(points: &[(f32, f32)], a: usize, b: usize) -> bool {
+    let path = points[a..=b]
+        .windows(2)
+        .map(|pair| distance(pair[0], pair[1]))
+        .sum::<f32>();
+
+    path == 0.0 || distance(points[a], points[b]) / path > LINE_RATIO
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// the closest of the 8 chain code directions from `a` to `b`.
+fn direction(a: (f32, f32), b: (f32, f32)) -> u8 {
+    let angle = (b.1 - a.1).atan2(b.0 - a.0);
+
+    ((angle / FRAC_PI_4).round() as i32).rem_euclid(8) as u8
+}
@@ -0,0 +1,172 @@
+use alloc::vec::Vec;
+use embassy_time::{Duration, Instant};
+
+use crate::{
+    SpellId,
+    binding::{Bindings, Shortcut, Trigger},
+};
+
+/// how long after a spell the next one in a combo can be cast.
+pub const DEFAULT_COMBO_TIMEOUT: Duration = Duration::from_millis(800);
+
+/// what to do once `Combos` knows what a trigger was part of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dispatch {
+    /// a combo was completed, run its shortcut.
+    Combo(Shortcut),
+    /// the trigger was not part of a combo, run what it is bound to.
+    Trigger(Trigger),
+}
+
+/// sequences of triggers bound to shortcuts, & the triggers cast so far that could still become
+/// one.
+pub struct Combos {
+    combos: Vec<(Vec<Trigger>, Shortcut)>,
+    pending: Vec<Trigger>,
+    deadline: Instant,
+    /// how long to wait for the next trigger of a combo.
+    pub timeout: Duration,
+}
+
+impl Default for Combos {
+    fn default() -> Self {
+        Self {
+            combos: Vec::new(),
+            pending: Vec::new(),
+            deadline: Instant::MAX,
+            timeout: DEFAULT_COMBO_TIMEOUT,
+        }
+    }
+}
+
+impl Combos {
+    /// binds the sequence to `shortcut`, replacing what it was bound to. returns the pending
+    /// triggers, as `unbind` does.
+    pub fn bind(&mut self, sequence: Vec<Trigger>, shortcut: Shortcut) -> Vec<Dispatch> {
+        let dispatches = self.unbind(&sequence);
+        self.combos.push((sequence, shortcut));
+        dispatches
+    }
+
+    /// unbinds the sequence. the pending triggers may have been waiting on it, so they are
+    /// dispatched rather than kept.
+    pub fn unbind(&mut self, sequence: &[Trigger]) -> Vec<Dispatch> {
+        self.combos.retain(|(bound, _)| bound != sequence);
+        self.flush()
+    }
+
+    /// true when `trigger` is part of any combo.
+    pub fn uses(&self, trigger: Trigger) -> bool {
+        self.combos
+            .iter()
+            .any(|(sequence, _)| sequence.contains(&trigger))
+    }
+
+    /// true when any combo has a held spell in it.
+    pub fn holds(&self) -> bool {
+        self.combos.iter().any(|(sequence, _)| {
+            sequence
+                .iter()
+                .any(|trigger| matches!(trigger, Trigger::Held(_)))
+        })
+    }
+
+    /// the trigger a cast spell is. holding it only makes it `Trigger::Held` if that is bound to
+    /// something on its own or in a combo, so a spell held by accident still casts & completes
+    /// combos as usual.
+    pub fn spell_trigger(&self, spell: SpellId, held: bool, bindings: &Bindings) -> Trigger {
+        let trigger = Trigger::Held(spell);
+
+        if held && (bindings.get(trigger).is_some() || self.uses(trigger)) {
+            trigger
+        } else {
+            Trigger::Spell(spell)
+        }
+    }
+
+    /// when the pending triggers should be given up on & dispatched, `None` if there are none.
+    pub fn deadline(&self) -> Option<Instant> {
+        (!self.pending.is_empty()).then_some(self.deadline)
+    }
+
+    /// adds a cast trigger. a combo fires as soon as it is complete, unless it is also the start
+    /// of a longer combo, then it waits for the next trigger or the timeout.
+    pub fn push(&mut self, trigger: Trigger, now: Instant) -> Vec<Dispatch> {
+        // past the deadline, the pending triggers are given up on even if `poll` has not been.
+        let mut dispatches = self.poll(now);
+        self.pending.push(trigger);
+
+        // the longest run of the latest triggers that is, or could become, a combo. the triggers
+        // before it went nowhere, so they are dispatched.
+        let start = (0..self.pending.len())
+            .find(|start| {
+                let tail = &self.pending[*start..];
+                self.is_prefix(tail) || self.exact(tail).is_some()
+            })
+            .unwrap_or(self.pending.len());
+        let tail = self.pending.split_off(start);
+        dispatches.extend(self.flush());
+
+        if tail.is_empty() {
+            return dispatches;
+        }
+
+        if self.is_prefix(&tail) {
+            self.pending = tail;
+            self.deadline = now + self.timeout;
+        } else if let Some(shortcut) = self.exact(&tail) {
+            dispatches.push(Dispatch::Combo(shortcut));
+        }
+
+        dispatches
+    }
+
+    /// dispatches the pending triggers once the deadline has passed.
+    pub fn poll(&mut self, now: Instant) -> Vec<Dispatch> {
+        if self.deadline().is_some_and(|deadline| now >= deadline) {
+            self.flush()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// dispatches every pending trigger, as the longest complete combos they start with or on
+    /// their own.
+    fn flush(&mut self) -> Vec<Dispatch> {
+        let mut dispatches = Vec::new();
+        let mut rest = &self.pending[..];
+
+        while !rest.is_empty() {
+            match (1..=rest.len())
+                .rev()
+                .find_map(|len| Some((len, self.exact(&rest[..len])?)))
+            {
+                Some((len, shortcut)) => {
+                    dispatches.push(Dispatch::Combo(shortcut));
+                    rest = &rest[len..];
+                }
+                None => {
+                    dispatches.push(Dispatch::Trigger(rest[0]));
+                    rest = &rest[1..];
+                }
+            }
+        }
+
+        self.pending.clear();
+        dispatches
+    }
+
+    fn exact(&self, sequence: &[Trigger]) -> Option<Shortcut> {
+        self.combos
+            .iter()
+            .find(|(bound, _)| bound == sequence)
+            .map(|(_, shortcut)| *shortcut)
+    }
+
+    /// true when a longer combo starts with `sequence`.
+    fn is_prefix(&self, sequence: &[Trigger]) -> bool {
+        self.combos
+            .iter()
+            .any(|(bound, _)| bound.len() > sequence.len() && bound.starts_with(sequence))
+    }
+}
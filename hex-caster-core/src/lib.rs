@@ -0,0 +1,31 @@
+//! the hardware independent half of hex-caster: turning touch-pad points into strokes & spells
+//! and recognizing them. kept out of the firmware so it can be tested on the host with
+//! `cargo test` from this directory.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use embassy_time::Duration;
+
+pub mod binding;
+pub mod chain_code;
+pub mod combo;
+pub mod pad;
+pub mod rubine;
+pub mod spell_caster;
+pub mod spell_compare;
+pub mod swipe;
+pub mod tap;
+pub mod timing;
+pub mod touch;
+
+pub type Point = (u16, u16);
+pub type SpellId = usize;
+/// the points drawn between touching the pad & lifting off.
+pub type Stroke = Vec<Point>;
+pub type Spell = Vec<Stroke>;
+/// when each point of a spell was drawn, as the time since its first point, stroke by stroke like
+/// the spell. empty for a spell that was not timed.
+pub type Timing = Vec<Vec<Duration>>;
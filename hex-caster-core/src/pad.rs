@@ -0,0 +1,182 @@
+use core::sync::atomic::{AtomicU16, Ordering};
+
+use crate::{Point, Spell};
+
+/// the largest x & y the touch-pad is assumed to report, until `set_pad_size` is given the
+/// logical maximums from its own report descriptor.
+pub const DEFAULT_PAD_SIZE: Point = (1_300, 800);
+
+/// the generic desktop usage page, & its x & y usages.
+const GENERIC_DESKTOP: u32 = 0x01;
+const X: u32 = 0x30;
+const Y: u32 = 0x31;
+
+static WIDTH: AtomicU16 = AtomicU16::new(DEFAULT_PAD_SIZE.0);
+static HEIGHT: AtomicU16 = AtomicU16::new(DEFAULT_PAD_SIZE.1);
+
+/// the largest x & y the touch-pad reports. spells drawn past it count as drawn on the edge.
+pub fn pad_size() -> Point {
+    (
+        WIDTH.load(Ordering::Relaxed),
+        HEIGHT.load(Ordering::Relaxed),
+    )
+}
+
+/// sets the size the pad reports, once it is known. spells are learned relative to it, so it is
+/// meant to be set once, before any are.
+pub fn set_pad_size((width, height): Point) {
+    WIDTH.store(width.max(1), Ordering::Relaxed);
+    HEIGHT.store(height.max(1), Ordering::Relaxed);
+}
+
+/// the logical maximums of the x & y inputs of a HID report descriptor, the largest of each if
+/// there are several (a touch-pad has x & y for every contact). `None` if it has no x or no y.
+pub fn logical_maximums(descriptor: &[u8]) -> Option<Point> {
+    // the global items that matter, & the stack `push` & `pop` keep them on.
+    let (mut usage_page, mut logical_maximum) = (0, 0);
+    let mut stack: [(u32, i32); 4] = [(0, 0); 4];
+    let mut depth = 0;
+    // the usages of the next main item, with their pages.
+    let mut usages: [(u32, u32); 8] = [(0, 0); 8];
+    let mut n_usages = 0;
+    let (mut x, mut y): (Option<u16>, Option<u16>) = (None, None);
+    let mut i = 0;
+
+    while i < descriptor.len() {
+        let prefix = descriptor[i];
+
+        // long items only carry vendor data.
+        if prefix == 0xfe {
+            i += 3 + *descriptor.get(i + 1)? as usize;
+            continue;
+        }
+
+        let size = [0, 1, 2, 4][(prefix & 0x03) as usize];
+        let data = descriptor.get(i + 1..i + 1 + size)?;
+        let unsigned = data
+            .iter()
+            .rev()
+            .fold(0, |value, byte| value << 8 | *byte as u32);
+        // sign extended, as logical extents are signed.
+        let signed = match size {
+            1 => unsigned as u8 as i8 as i32,
+            2 => unsigned as u16 as i16 as i32,
+            _ => unsigned as i32,
+        };
+        i += 1 + size;
+
+        match prefix & 0xfc {
+            // usage page, logical maximum, push & pop.
+            0x04 => usage_page = unsigned,
+            0x24 => logical_maximum = signed,
+            0xa4 if depth < stack.len() => {
+                stack[depth] = (usage_page, logical_maximum);
+                depth += 1;
+            }
+            0xb4 if depth > 0 => {
+                depth -= 1;
+                (usage_page, logical_maximum) = stack[depth];
+            }
+            // a usage, with its own page if it is 4 bytes long.
+            0x08 if n_usages < usages.len() => {
+                usages[n_usages] = match size {
+                    4 => (unsigned >> 16, unsigned & 0xffff),
+                    _ => (usage_page, unsigned),
+                };
+                n_usages += 1;
+            }
+            // an input, which is the x or y if one of its usages is.
+            0x80 => {
+                let maximum = u16::try_from(logical_maximum).ok();
+
+                for usage in usages[..n_usages].iter() {
+                    match *usage {
+                        (GENERIC_DESKTOP, X) => x = x.max(maximum),
+                        (GENERIC_DESKTOP, Y) => y = y.max(maximum),
+                        _ => {}
+                    }
+                }
+
+                n_usages = 0;
+            }
+            // the other main items use up the usages too.
+            0x90 | 0xa0 | 0xb0 | 0xc0 => n_usages = 0,
+            _ => {}
+        }
+    }
+
+    Some((x?, y?))
+}
+
+/// where on the pad a spell was drawn, the pad split into a 3x3 grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PadRegion {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Centre,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl PadRegion {
+    const GRID: [[Self; 3]; 3] = [
+        [Self::TopLeft, Self::Top, Self::TopRight],
+        [Self::Left, Self::Centre, Self::Right],
+        [Self::BottomLeft, Self::Bottom, Self::BottomRight],
+    ];
+
+    /// the region holding the centre of the spell's bounding box, `None` if the spell is empty.
+    pub fn of(spell: &Spell) -> Option<Self> {
+        let ((min_x, min_y), (max_x, max_y)) = bounding_box(spell)?;
+        let third = |v: u16, max: u16| (v as usize * 3 / (max as usize + 1)).min(2);
+        let centre = (
+            ((min_x as u32 + max_x as u32) / 2) as u16,
+            ((min_y as u32 + max_y as u32) / 2) as u16,
+        );
+
+        let (width, height) = pad_size();
+
+        Some(Self::GRID[third(centre.1, height)][third(centre.0, width)])
+    }
+}
+
+/// how big a spell was drawn, by the longest side of its bounding box measured against the pad's
+/// height.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeClass {
+    /// under a quarter of the pad's height.
+    Small,
+    Medium,
+    /// over half of the pad's height.
+    Large,
+}
+
+impl SizeClass {
+    /// the size of the spell, `None` if the spell is empty.
+    pub fn of(spell: &Spell) -> Option<Self> {
+        let ((min_x, min_y), (max_x, max_y)) = bounding_box(spell)?;
+        let longest = (max_x - min_x).max(max_y - min_y);
+        let height = pad_size().1;
+
+        Some(if longest < height / 4 {
+            Self::Small
+        } else if longest <= height / 2 {
+            Self::Medium
+        } else {
+            Self::Large
+        })
+    }
+}
+
+/// the top left & bottom right corners of the raw points, `None` if the spell is empty.
+pub fn bounding_box(spell: &Spell) -> Option<(Point, Point)> {
+    spell.iter().flatten().fold(None, |bounds, &(x, y)| {
+        let ((min_x, min_y), (max_x, max_y)) = bounds.unwrap_or(((x, y), (x, y)));
+
+        Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))))
+    })
+}
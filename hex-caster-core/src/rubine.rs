@@ -0,0 +1,531 @@
+// https://dl.acm.org/doi/10.1145/127719.122753 (Rubine, "Specifying Gestures by Example")
+
+use core::{cmp::Ordering, f32::consts::PI};
+
+use alloc::{boxed::Box, vec::Vec};
+use embassy_futures::yield_now;
+use log::*;
+// unused when the tests pull in `std`, as in `spell_compare`.
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use num_traits::Float;
+
+use crate::{
+    Point, Spell, SpellId, Timing,
+    pad::{PadRegion, SizeClass, pad_size},
+    spell_compare::{MIN_POINTS, RecognizeError, Recognizer, SpellOptions, mirrored, reversed},
+    timing::Tempo,
+};
+
+/// how many features describe a spell.
+pub const FEATURES: usize = 13;
+/// a spell boiled down to Rubine's features, see `features`.
+pub type Features = [f32; FEATURES];
+
+/// points closer than this to the last kept point are dropped, so jitter does not add turning.
+const MIN_STEP: f32 = 5.0;
+/// how far from the first point the initial angle is measured, in pad units.
+const INITIAL_DISTANCE: f32 = 50.0;
+/// the variance assumed for every feature before any examples are seen, so a spell with a single
+/// example is classified by its distance to the mean rather than not at all.
+const PRIOR_VARIANCE: f32 = 0.05;
+/// how many examples `PRIOR_VARIANCE` is worth against the variance of the learned examples.
+const PRIOR_WEIGHT: f32 = 2.0;
+/// Rubine's outlier rule, a spell further than this (squared mahalanobis distance) from the mean
+/// of a spell never matches it.
+const MAX_DISTANCE: f32 = 0.5 * (FEATURES * FEATURES) as f32;
+
+struct Example {
+    id: SpellId,
+    options: SpellOptions,
+    /// every feature, the time ones too even if the spell is not `timed`.
+    features: Features,
+    cast: Cast,
+}
+
+/// the mean of one spell, which a cast spell's distance is measured from.
+struct Class {
+    id: SpellId,
+    /// the options of the spell's first example.
+    options: SpellOptions,
+    /// the ways the spell's examples were drawn, each kept once, so a cast spell is checked
+    /// against them without going through every example.
+    casts: Vec<Cast>,
+    mean: Features,
+}
+
+/// Rubine's global features of a spell, classified by the mahalanobis distance to each spell's
+/// mean under a covariance pooled over every spell. Rubine's linear weights drop the part of the
+/// distance every spell shares, which only holds if every spell compares the same features, &
+/// the time ones are only compared for `timed` spells. it is retrained whenever an example is
+/// learned or forgotten, after which classifying costs the same however many examples there are.
+/// rotation insensitive spells are not supported, the examples have to show how the spell is
+/// turned.
+#[derive(Default)]
+pub struct RubineRecognizer {
+    examples: Vec<Example>,
+    classes: Vec<Class>,
+    /// the inverse of the covariance of the features, pooled over every spell. boxed, as it is
+    /// much bigger than the other recognizers.
+    inverse: Box<[Features; FEATURES]>,
+}
+
+impl RubineRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// recomputes the mean of each spell & the inverse of the pooled covariance.
+    fn train(&mut self) {
+        let mut ids: Vec<SpellId> = self.examples.iter().map(|example| example.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let means: Vec<Features> = ids
+            .iter()
+            .map(|id| {
+                let examples = self.examples.iter().filter(|example| example.id == *id);
+                let n = examples.clone().count() as f32;
+                let mut mean = [0.0; FEATURES];
+
+                for example in examples {
+                    for (m, f) in mean
+                        .iter_mut()
+                        .zip(masked(&example.features, &example.options))
+                    {
+                        *m += f / n;
+                    }
+                }
+
+                mean
+            })
+            .collect();
+
+        let mut covariance = [[0.0; FEATURES]; FEATURES];
+
+        for example in self.examples.iter() {
+            let i = ids.binary_search(&example.id).unwrap_or_default();
+            let d = sub(&masked(&example.features, &example.options), &means[i]);
+
+            for (row, a) in covariance.iter_mut().zip(d) {
+                for (c, b) in row.iter_mut().zip(d) {
+                    *c += a * b;
+                }
+            }
+        }
+
+        // shrunk towards `PRIOR_VARIANCE`, which also keeps it invertible.
+        let dof = self.examples.len().saturating_sub(ids.len()) as f32;
+
+        for (i, row) in covariance.iter_mut().enumerate() {
+            for (j, c) in row.iter_mut().enumerate() {
+                let prior = if i == j {
+                    PRIOR_WEIGHT * PRIOR_VARIANCE
+                } else {
+                    0.0
+                };
+                *c = (*c + prior) / (dof + PRIOR_WEIGHT);
+            }
+        }
+
+        *self.inverse = invert(covariance);
+        self.classes = ids
+            .into_iter()
+            .zip(means)
+            .map(|(id, mean)| {
+                let examples = || self.examples.iter().filter(|example| example.id == id);
+                let mut casts: Vec<Cast> = Vec::new();
+
+                for example in examples() {
+                    if !casts.contains(&example.cast) {
+                        casts.push(example.cast);
+                    }
+                }
+
+                Class {
+                    id,
+                    options: examples()
+                        .next()
+                        .map(|example| example.options)
+                        .unwrap_or_default(),
+                    casts,
+                    mean,
+                }
+            })
+            .collect();
+        debug!(
+            "trained on {} examples of {} spells",
+            self.examples.len(),
+            self.classes.len()
+        );
+    }
+
+    /// how likely `spell` is to be each learned spell, from Rubine's estimate of the probability
+    /// of the best class. spells with no example drawn the same way as `spell` (stroke count,
+    /// region & size as their options ask) score 0, as do outliers.
+    fn scores(
+        &self,
+        spell: &Spell,
+        timing: &Timing,
+    ) -> Result<Vec<(SpellId, f32)>, RecognizeError> {
+        let eligible = self.eligible(&Cast::of(spell)?);
+        // the reversed & mirrored spells take as long to draw, at the same speed.
+        let tempo = Tempo::of(spell, timing);
+        let variants = [
+            Some(features(spell, tempo.as_ref())?),
+            features(&reversed(spell), tempo.as_ref()).ok(),
+            features(&mirrored(spell, true), tempo.as_ref()).ok(),
+            features(&mirrored(spell, false), tempo.as_ref()).ok(),
+        ];
+        let mut scores: Vec<(SpellId, f32)> =
+            self.classes.iter().map(|class| (class.id, 0.0)).collect();
+
+        for (variant, features) in variants.iter().enumerate() {
+            let Some(features) = features else {
+                continue;
+            };
+
+            for (i, p) in self
+                .probabilities(features, &eligible)
+                .into_iter()
+                .enumerate()
+            {
+                let options = self.classes[i].options;
+                let allowed = match variant {
+                    0 => true,
+                    1 => !options.direction_sensitive,
+                    _ => options.mirror_invariant,
+                };
+
+                if allowed {
+                    scores[i].1 = scores[i].1.max(p);
+                }
+            }
+        }
+
+        Ok(scores)
+    }
+
+    /// which spells have an example drawn like `cast`.
+    fn eligible(&self, cast: &Cast) -> Vec<bool> {
+        self.classes
+            .iter()
+            .map(|class| {
+                class
+                    .casts
+                    .iter()
+                    .any(|other| cast.like(other, &class.options))
+            })
+            .collect()
+    }
+
+    /// the probability of each spell, among the `eligible` ones, from the squared mahalanobis
+    /// distances to each mean.
+    fn probabilities(&self, features: &Features, eligible: &[bool]) -> Vec<f32> {
+        let distances: Vec<f32> = self
+            .classes
+            .iter()
+            .map(|class| {
+                let d = sub(&masked(features, &class.options), &class.mean);
+                dot(&d, &mul(&self.inverse, &d))
+            })
+            .collect();
+
+        self.classes
+            .iter()
+            .enumerate()
+            .map(|(i, class)| {
+                if !eligible[i] {
+                    0.0
+                } else if distances[i] > MAX_DISTANCE {
+                    trace!("spell {} is an outlier", class.id);
+                    0.0
+                } else {
+                    1.0 / distances
+                        .iter()
+                        .zip(eligible)
+                        .filter(|(_, eligible)| **eligible)
+                        .map(|(d, _)| (0.5 * (distances[i] - d)).exp())
+                        .sum::<f32>()
+                }
+            })
+            .collect()
+    }
+}
+
+impl Recognizer for RubineRecognizer {
+    type Template = Features;
+
+    fn normalize(&self, spell: &Spell, timing: &Timing) -> Result<Self::Template, RecognizeError> {
+        features(spell, Tempo::of(spell, timing).as_ref())
+    }
+
+    fn add_template(
+        &mut self,
+        id: SpellId,
+        spell: &Spell,
+        timing: &Timing,
+        options: SpellOptions,
+    ) -> Result<(), RecognizeError> {
+        let cast = Cast::of(spell)?;
+
+        if !options.rotation_sensitive && !self.examples.iter().any(|example| example.id == id) {
+            warn!("rubine can not match spell {id} at any angle, only as its examples are turned");
+        }
+
+        self.examples.push(Example {
+            id,
+            options,
+            features: self.normalize(spell, timing)?,
+            cast,
+        });
+        self.train();
+
+        Ok(())
+    }
+
+    async fn classify(
+        &self,
+        spell: &Spell,
+        timing: &Timing,
+    ) -> Result<Vec<(SpellId, f32)>, RecognizeError> {
+        if self.classes.is_empty() {
+            return Err(RecognizeError::EmptyCorpus);
+        }
+
+        let mut scores = self.scores(spell, timing)?;
+        scores.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+        Ok(scores)
+    }
+
+    /// the features describe the whole spell, so there is nothing to compare an unfinished one
+    /// to.
+    async fn classify_prefix(&self, _spell: &Spell) -> Result<Vec<(SpellId, f32)>, RecognizeError> {
+        Ok(Vec::new())
+    }
+
+    fn remove_template(&mut self, id: SpellId) {
+        self.examples.retain(|example| example.id != id);
+        self.train();
+    }
+
+    async fn example_scores(&self, id: SpellId) -> Vec<f32> {
+        let Some(i) = self.classes.iter().position(|class| class.id == id) else {
+            return Vec::new();
+        };
+        // only the features of the examples are kept, so each is scored as if it was cast.
+        let mut scores = Vec::new();
+
+        for example in self.examples.iter().filter(|example| example.id == id) {
+            let eligible = self.eligible(&example.cast);
+            scores.push(self.probabilities(&example.features, &eligible)[i]);
+            yield_now().await;
+        }
+
+        // a single example says nothing about how much the spell varies.
+        if scores.len() < 2 {
+            scores.clear();
+        }
+
+        scores
+    }
+}
+
+/// what, besides its features, decides which spells a cast spell can be.
+#[derive(Clone, Copy, PartialEq)]
+struct Cast {
+    strokes: usize,
+    region: Option<PadRegion>,
+    size: Option<SizeClass>,
+}
+
+impl Cast {
+    fn of(spell: &Spell) -> Result<Self, RecognizeError> {
+        if spell.iter().map(|stroke| stroke.len()).sum::<usize>() < MIN_POINTS {
+            return Err(RecognizeError::TooShort);
+        }
+
+        Ok(Self {
+            strokes: spell.len(),
+            region: PadRegion::of(spell),
+            size: SizeClass::of(spell),
+        })
+    }
+
+    /// true when drawn like `other`, as far as a spell with `options` cares.
+    fn like(&self, other: &Cast, options: &SpellOptions) -> bool {
+        self.strokes == other.strokes
+            && (!options.position_aware || self.region == other.region)
+            && (!options.size_aware || self.size == other.size)
+    }
+}
+
+/// Rubine's features:
+///
+/// 0, 1. the cosine & sine of the angle the spell starts off at.
+/// 2, 3. the length & angle of the bounding box's diagonal.
+/// 4, 5, 6. the distance from the first point to the last, & the cosine & sine of its angle.
+/// 7. the length of the path.
+/// 8, 9, 10. the total turning, absolute turning & squared turning along the path.
+/// 11, 12. the fastest the finger moved & how long it took, both 0 if the spell was not timed.
+///    the recognizer only compares them for spells learned as `timed`.
+///
+/// lengths are relative to the pad's diagonal, so every feature is about as big. for the same
+/// reason the top speed is not squared, as it is in the paper. the strokes of a multistroke spell
+/// are taken in the order they were drawn, the gaps between them are skipped.
+pub fn features(spell: &Spell, tempo: Option<&Tempo>) -> Result<Features, RecognizeError> {
+    let strokes: Vec<Vec<(f32, f32)>> = spell.iter().map(|stroke| deduped(stroke)).collect();
+    let points = || strokes.iter().flatten().copied();
+    let (first, last) = match (points().next(), points().last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Err(RecognizeError::TooShort),
+    };
+
+    let (mut min, mut max) = (first, first);
+    for (x, y) in points() {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    let (width, height) = (max.0 - min.0, max.1 - min.1);
+
+    if width == 0.0 && height == 0.0 {
+        return Err(RecognizeError::Degenerate);
+    }
+
+    let pad = pad_size();
+    let scale = (pad.0 as f32).hypot(pad.1 as f32);
+    let initial = points()
+        .find(|p| distance(first, *p) >= INITIAL_DISTANCE)
+        .unwrap_or(last);
+    let (initial_cos, initial_sin) = direction(first, initial);
+    let (end_cos, end_sin) = direction(first, last);
+
+    let mut length = 0.0;
+    let (mut turning, mut absolute_turning, mut squared_turning) = (0.0, 0.0, 0.0);
+
+    for stroke in strokes.iter() {
+        for pair in stroke.windows(2) {
+            length += distance(pair[0], pair[1]);
+        }
+
+        for triple in stroke.windows(3) {
+            let a = (triple[1].0 - triple[0].0, triple[1].1 - triple[0].1);
+            let b = (triple[2].0 - triple[1].0, triple[2].1 - triple[1].1);
+            let theta = (a.0 * b.1 - a.1 * b.0).atan2(a.0 * b.0 + a.1 * b.1);
+
+            turning += theta;
+            absolute_turning += theta.abs();
+            squared_turning += theta * theta;
+        }
+    }
+
+    Ok([
+        initial_cos,
+        initial_sin,
+        width.hypot(height) / scale,
+        height.atan2(width),
+        distance(first, last) / scale,
+        end_cos,
+        end_sin,
+        length / scale,
+        turning / PI,
+        absolute_turning / PI,
+        squared_turning / (PI * PI),
+        tempo.map_or(0.0, |tempo| tempo.max_speed / scale),
+        tempo.map_or(0.0, |tempo| tempo.duration),
+    ])
+}
+
+/// the features a spell with `options` is compared by, the time ones are 0 unless it is `timed`.
+fn masked(features: &Features, options: &SpellOptions) -> Features {
+    let mut features = *features;
+
+    if !options.timed {
+        features[FEATURES - 2..].fill(0.0);
+    }
+
+    features
+}
+
+/// the stroke's points as floats, dropping those within `MIN_STEP` of the last one kept.
+fn deduped(stroke: &[Point]) -> Vec<(f32, f32)> {
+    let mut points: Vec<(f32, f32)> = Vec::with_capacity(stroke.len());
+
+    for (x, y) in stroke.iter().map(|(x, y)| (*x as f32, *y as f32)) {
+        if points
+            .last()
+            .is_none_or(|last| distance(*last, (x, y)) >= MIN_STEP)
+        {
+            points.push((x, y));
+        }
+    }
+
+    points
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// the cosine & sine of the angle from `a` to `b`, both 0 if they are the same point.
+fn direction(a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    let d = distance(a, b);
+
+    if d == 0.0 {
+        (0.0, 0.0)
+    } else {
+        ((b.0 - a.0) / d, (b.1 - a.1) / d)
+    }
+}
+
+fn sub(a: &Features, b: &Features) -> Features {
+    core::array::from_fn(|i| a[i] - b[i])
+}
+
+fn dot(a: &Features, b: &Features) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn mul(m: &[Features; FEATURES], v: &Features) -> Features {
+    core::array::from_fn(|i| dot(&m[i], v))
+}
+
+/// gauss-jordan elimination with partial pivoting. the covariance is shrunk towards a diagonal,
+/// so it is always invertible.
+fn invert(mut m: [Features; FEATURES]) -> [Features; FEATURES] {
+    let mut inverse: [Features; FEATURES] =
+        core::array::from_fn(|i| core::array::from_fn(|j| if i == j { 1.0 } else { 0.0 }));
+
+    for col in 0..FEATURES {
+        let pivot = (col..FEATURES)
+            .max_by(|a, b| {
+                m[*a][col]
+                    .abs()
+                    .partial_cmp(&m[*b][col].abs())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap_or(col);
+        m.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let p = m[col][col];
+        for j in 0..FEATURES {
+            m[col][j] /= p;
+            inverse[col][j] /= p;
+        }
+
+        for row in 0..FEATURES {
+            if row == col {
+                continue;
+            }
+
+            let factor = m[row][col];
+            for j in 0..FEATURES {
+                m[row][j] -= factor * m[col][j];
+                inverse[row][j] -= factor * inverse[col][j];
+            }
+        }
+    }
+
+    inverse
+}
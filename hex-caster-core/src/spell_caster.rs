@@ -0,0 +1,193 @@
+use crate::{
+    Point, Spell, Stroke, Timing,
+    tap::{TAP_SLOP, Tap},
+    touch::TouchReport,
+};
+use alloc::vec::Vec;
+use embassy_time::{Duration, Instant};
+
+/// how long the finger has to stay off the pad before a spell is cast. lifting & touching down
+/// again within this time starts another stroke of the same spell ("X", "=", "+").
+pub const STROKE_GAP: Duration = Duration::from_millis(300);
+/// how many new points are drawn between two previews of an unfinished spell.
+pub const PREVIEW_EVERY: usize = 16;
+/// how long the finger has to rest at the end of a stroke before lifting for the spell to be
+/// held, see `SpellBuilder::held`.
+pub const HOLD_TIME: Duration = Duration::from_millis(500);
+
+pub struct SpellBuilder {
+    strokes: Vec<Stroke>,
+    /// when each point of `strokes` was drawn, since `started_at`.
+    times: Timing,
+    last_point: Point,
+    /// when the first point of the spell was drawn.
+    started_at: Instant,
+    touched_at: Instant,
+    lifted_at: Instant,
+    /// how long each stroke was touching the pad.
+    durations: Vec<Duration>,
+    /// the most fingers on the pad at once.
+    fingers: u8,
+    /// how many points had been drawn at the last preview.
+    previewed: usize,
+    /// how long the finger has to rest at the end of a stroke for the spell to be held, `None` if
+    /// spells are never held.
+    pub hold_time: Option<Duration>,
+    /// where the finger came to rest & when. it is resting while it stays within `TAP_SLOP`.
+    rested_at: Point,
+    resting_since: Instant,
+    /// whether the finger has left where it touched down, so a long press is not a hold.
+    moved: bool,
+    /// whether the last stroke ended in a hold.
+    held: bool,
+    /// whether `hold_reached` has already told of the current rest.
+    hold_noticed: bool,
+}
+
+impl Default for SpellBuilder {
+    fn default() -> Self {
+        Self {
+            strokes: Vec::new(),
+            times: Vec::new(),
+            last_point: (0, 0),
+            started_at: Instant::MIN,
+            touched_at: Instant::MIN,
+            lifted_at: Instant::MIN,
+            durations: Vec::new(),
+            fingers: 0,
+            previewed: 0,
+            hold_time: Some(HOLD_TIME),
+            rested_at: (0, 0),
+            resting_since: Instant::MIN,
+            moved: false,
+            held: false,
+            hold_noticed: false,
+        }
+    }
+}
+
+impl SpellBuilder {
+    pub fn step(&mut self, point: Point) {
+        if point != (0, 0) && point != self.last_point {
+            if self.last_point == (0, 0) {
+                self.touched_at = Instant::now();
+
+                if self.strokes.is_empty() {
+                    self.started_at = self.touched_at;
+                }
+
+                self.strokes.push(Vec::with_capacity(1_000));
+                self.times.push(Vec::with_capacity(1_000));
+                self.rest(point);
+                self.moved = false;
+                self.held = false;
+            } else if point.0.abs_diff(self.rested_at.0) > TAP_SLOP
+                || point.1.abs_diff(self.rested_at.1) > TAP_SLOP
+            {
+                self.rest(point);
+                self.moved = true;
+            }
+
+            if let (Some(stroke), Some(times)) = (self.strokes.last_mut(), self.times.last_mut()) {
+                stroke.push(point);
+                times.push(Instant::now() - self.started_at);
+            }
+        } else if point == (0, 0) && self.last_point != (0, 0) {
+            self.lifted_at = Instant::now();
+            self.durations.push(self.lifted_at - self.touched_at);
+            self.held = self.is_holding(self.lifted_at);
+        }
+
+        self.last_point = point;
+    }
+
+    /// starts a rest of the finger at `point`, which it has just moved to.
+    fn rest(&mut self, point: Point) {
+        self.rested_at = point;
+        self.resting_since = Instant::now();
+        self.hold_noticed = false;
+    }
+
+    /// whether the finger, having moved, has been resting for `hold_time` at `now`.
+    fn is_holding(&self, now: Instant) -> bool {
+        self.moved
+            && self
+                .hold_time
+                .is_some_and(|hold_time| now - self.resting_since >= hold_time)
+    }
+
+    /// true once per rest, when the finger still on the pad has rested long enough to hold, so it
+    /// can be told before it lifts.
+    pub fn hold_reached(&mut self) -> bool {
+        if self.last_point == (0, 0) || self.hold_noticed || !self.is_holding(Instant::now()) {
+            return false;
+        }
+
+        self.hold_noticed = true;
+
+        true
+    }
+
+    /// whether the finger rested for `hold_time` at the end of the last stroke before lifting,
+    /// which casts the spell's held variant (`Trigger::Held`) rather than the spell.
+    pub fn held(&self) -> bool {
+        self.held
+    }
+
+    /// steps with the first finger's position, counting every finger.
+    pub fn step_report(&mut self, report: &TouchReport) {
+        self.fingers = self.fingers.max(report.contacts().len() as u8);
+        self.step(report.point());
+    }
+
+    pub fn should_cast(&self) -> bool {
+        self.last_point == (0, 0)
+            && !self.strokes.is_empty()
+            && self.lifted_at.elapsed() >= STROKE_GAP
+    }
+
+    pub fn build(&self) -> Spell {
+        self.strokes.clone()
+    }
+
+    /// when each point of the spell was drawn, see `Timing`.
+    pub fn timing(&self) -> Timing {
+        self.times.clone()
+    }
+
+    /// the unfinished spell, every `PREVIEW_EVERY` points of a first stroke still being drawn.
+    pub fn preview(&mut self) -> Option<Spell> {
+        let [stroke] = self.strokes.as_slice() else {
+            return None;
+        };
+
+        if self.last_point == (0, 0) || stroke.len() < self.previewed + PREVIEW_EVERY {
+            return None;
+        }
+
+        self.previewed = stroke.len();
+
+        Some(self.strokes.clone())
+    }
+
+    /// the most fingers that were on the pad at once while drawing the spell.
+    pub fn fingers(&self) -> u8 {
+        self.fingers.max(1)
+    }
+
+    /// the tap the strokes make, if they are taps rather than a spell.
+    pub fn tap(&self) -> Option<Tap> {
+        Tap::classify(&self.strokes, &self.durations)
+    }
+
+    pub fn reset(&mut self) {
+        self.strokes.clear();
+        self.times.clear();
+        self.durations.clear();
+        self.fingers = 0;
+        self.previewed = 0;
+        self.moved = false;
+        self.held = false;
+        self.last_point = (0, 0);
+    }
+}
@@ -0,0 +1,1432 @@
+// https://depts.washington.edu/acelab/proj/dollar/dollar.pdf
+// https://dl.acm.org/doi/10.1145/1753326.1753654 (Protractor)
+// https://depts.washington.edu/acelab/proj/dollar/pdollar.pdf ($P)
+
+use core::{
+    cmp::Ordering,
+    f32::consts::{FRAC_PI_2, PI},
+    fmt,
+    str::FromStr,
+};
+
+use alloc::{boxed::Box, vec::Vec};
+use embassy_futures::yield_now;
+use log::*;
+// the tests build embassy-time with `std`, whose inherent float methods make this unused.
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use num_traits::Float;
+
+use crate::{
+    Point, Spell, SpellId, Timing,
+    chain_code::ShortStraw,
+    pad::{PadRegion, SizeClass},
+    rubine::RubineRecognizer,
+    timing::Tempo,
+};
+
+pub type NormedPoint = (f32, f32);
+/// a spell resampled to `N` points.
+pub type NormedSpell = [NormedPoint; N];
+/// a `NormedSpell` flattened to `[x0, y0, x1, y1, ..]` & scaled to unit length.
+pub type Vector = [f32; 2 * N];
+
+/// per-spell matching behaviour, chosen when the spell is learned.
+#[derive(Clone, Copy, Debug)]
+pub struct SpellOptions {
+    /// when false, the cast spell is first turned to the template's indicative angle so the spell
+    /// matches however it is drawn (a circle). when true only the `THETA` search around the drawn
+    /// orientation is done, so "arrow up" and "arrow right" stay distinct.
+    pub rotation_sensitive: bool,
+    /// when false the spell can be drawn from either end, it is also compared against its
+    /// reversed path. when true a clockwise & a counter-clockwise circle are different spells.
+    /// $P ignores the order of the points, so it never tells the two directions apart.
+    pub direction_sensitive: bool,
+    /// when true the horizontally & vertically mirrored cast spell are also compared, so the
+    /// spell matches when drawn with the other hand.
+    pub mirror_invariant: bool,
+    /// when true the spell only matches when drawn in the same region of the pad as one of its
+    /// examples, so one shape can be several spells.
+    pub position_aware: bool,
+    /// when true the spell only matches when drawn about as big as one of its examples, so a
+    /// small & a large circle can be different spells.
+    pub size_aware: bool,
+    /// when true the points of a single stroke spell are matched up with dynamic time warping
+    /// (`Dtw`), whatever the backend, so a stroke drawn with an uneven rhythm still lines up with
+    /// its examples.
+    pub time_warped: bool,
+    /// when true the spell only matches when drawn in about the same time & with the same speed
+    /// profile as one of its examples, so a quick flick & a slow drag can be different spells.
+    /// timed spells are never recognized early, their timing is only known once they are done.
+    pub timed: bool,
+}
+
+impl Default for SpellOptions {
+    fn default() -> Self {
+        Self {
+            rotation_sensitive: true,
+            direction_sensitive: true,
+            mirror_invariant: false,
+            position_aware: false,
+            size_aware: false,
+            time_warped: false,
+            timed: false,
+        }
+    }
+}
+
+/// why a spell could not be normalized or recognized.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecognizeError {
+    /// fewer than `MIN_POINTS` points were drawn.
+    TooShort,
+    /// every point is in the same place, so there is no shape to compare.
+    Degenerate,
+    /// no spells have been learned to compare against.
+    EmptyCorpus,
+    /// the example was drawn with a different number of fingers than the spell it was added to.
+    WrongFingers,
+    /// the example was added to a spell that was never learned.
+    UnknownSpell,
+}
+
+impl fmt::Display for RecognizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "gesture too short"),
+            Self::Degenerate => write!(f, "gesture has no length or area"),
+            Self::EmptyCorpus => write!(f, "no spells learned yet"),
+            Self::WrongFingers => write!(f, "spell is drawn with a different number of fingers"),
+            Self::UnknownSpell => write!(f, "no such spell"),
+        }
+    }
+}
+
+/// which algorithm scores a cast spell against the learned examples.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Backend {
+    /// the $1 golden-section search for the best angle, one path distance per step.
+    #[default]
+    DollarOne,
+    /// Protractor's closed-form best angle over the vectorized templates, constant cost per
+    /// template.
+    Protractor,
+    /// $P point cloud matching, ignores stroke order & direction. always used for multistroke
+    /// spells, whatever the backend.
+    DollarP,
+    /// ShortStraw's corners as a chain code, compared by edit distance, see `chain_code`. for
+    /// polylines like "Z" or arrows.
+    ShortStraw,
+    /// Rubine's global features like the path length & total turning, classified by the distance
+    /// to each spell's mean, see `rubine`. it needs a few examples of each spell but classifies in constant time.
+    Rubine,
+}
+
+impl FromStr for Backend {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "$1" | "1" | "dollar-one" => Ok(Self::DollarOne),
+            "protractor" => Ok(Self::Protractor),
+            "$p" | "p" | "dollar-p" => Ok(Self::DollarP),
+            "shortstraw" | "chain-code" => Ok(Self::ShortStraw),
+            "rubine" => Ok(Self::Rubine),
+            _ => Err(()),
+        }
+    }
+}
+
+/// a gesture recognition algorithm & the templates it has learned.
+#[allow(async_fn_in_trait)]
+pub trait Recognizer {
+    /// a spell in the form this recognizer compares.
+    type Template;
+
+    fn normalize(&self, spell: &Spell, timing: &Timing) -> Result<Self::Template, RecognizeError>;
+
+    /// learns `spell` as an example of the spell `id`.
+    fn add_template(
+        &mut self,
+        id: SpellId,
+        spell: &Spell,
+        timing: &Timing,
+        options: SpellOptions,
+    ) -> Result<(), RecognizeError>;
+
+    /// scores `spell` against every learned spell, best match first. yields to the executor
+    /// between templates so other tasks on the core keep running.
+    async fn classify(
+        &self,
+        spell: &Spell,
+        timing: &Timing,
+    ) -> Result<Vec<(SpellId, f32)>, RecognizeError>;
+
+    /// like `classify`, but for a single stroke that is still being drawn. it is also scored
+    /// against the start of each single stroke spell.
+    async fn classify_prefix(&self, spell: &Spell) -> Result<Vec<(SpellId, f32)>, RecognizeError>;
+
+    /// forgets every example of the spell `id`.
+    fn remove_template(&mut self, id: SpellId);
+
+    /// how the examples of the spell `id` score against each other, used to derive its threshold.
+    async fn example_scores(&self, id: SpellId) -> Vec<f32>;
+}
+
+/// the normalization & comparison of a template matching algorithm, for single stroke spells.
+pub trait Matcher {
+    type Template;
+
+    fn normalize(&self, spell: &Spell) -> Result<Self::Template, RecognizeError>;
+
+    /// how well `cast_spell` matches `template`, from 0 (nothing alike) to 1 (identical).
+    fn score(
+        &self,
+        cast_spell: &Self::Template,
+        template: &Self::Template,
+        options: &SpellOptions,
+    ) -> f32;
+}
+
+pub struct DollarOne;
+
+impl Matcher for DollarOne {
+    type Template = NormedSpell;
+
+    fn normalize(&self, spell: &Spell) -> Result<Self::Template, RecognizeError> {
+        process_stroke(spell)
+    }
+
+    fn score(
+        &self,
+        cast_spell: &Self::Template,
+        template: &Self::Template,
+        options: &SpellOptions,
+    ) -> f32 {
+        let d = example_distance(cast_spell, template, options, false);
+
+        score(d, SIZE)
+    }
+}
+
+pub struct Protractor;
+
+impl Matcher for Protractor {
+    type Template = Vector;
+
+    fn normalize(&self, spell: &Spell) -> Result<Self::Template, RecognizeError> {
+        Ok(vectorize(&process_stroke(spell)?))
+    }
+
+    fn score(
+        &self,
+        cast_spell: &Self::Template,
+        template: &Self::Template,
+        options: &SpellOptions,
+    ) -> f32 {
+        let d = optimal_cosine_distance(cast_spell, template, options);
+
+        // maps the angle between the vectors onto the same 0..1 range as the $1 score.
+        1.0 - d / FRAC_PI_2
+    }
+}
+
+pub struct DollarP;
+
+impl Matcher for DollarP {
+    /// the point cloud, resampled along each stroke without joining them.
+    type Template = NormedSpell;
+
+    fn normalize(&self, spell: &Spell) -> Result<Self::Template, RecognizeError> {
+        process_cloud(spell)
+    }
+
+    fn score(
+        &self,
+        cast_spell: &Self::Template,
+        template: &Self::Template,
+        _options: &SpellOptions,
+    ) -> f32 {
+        cloud_score(cast_spell, template)
+    }
+}
+
+/// $1, but the cast spell's points are matched to the template's with dynamic time warping
+/// instead of point i to point i. used for `time_warped` spells.
+pub struct Dtw;
+
+impl Matcher for Dtw {
+    type Template = NormedSpell;
+
+    fn normalize(&self, spell: &Spell) -> Result<Self::Template, RecognizeError> {
+        process_stroke(spell)
+    }
+
+    fn score(
+        &self,
+        cast_spell: &Self::Template,
+        template: &Self::Template,
+        options: &SpellOptions,
+    ) -> f32 {
+        let d = example_distance(cast_spell, template, options, true);
+
+        score(d, SIZE)
+    }
+}
+
+/// a matcher's template, along with the $P cloud of multistroke spells. the optional templates are
+/// boxed so the (many) single stroke, direction sensitive entries stay small.
+pub struct Normalized<T> {
+    pub template: T,
+    pub cloud: Option<Box<NormedSpell>>,
+    /// the template of the reversed path, for single stroke spells that are not direction
+    /// sensitive.
+    pub reversed: Option<Box<T>>,
+    /// where on the pad the spell was drawn, before normalization threw it away.
+    pub region: Option<PadRegion>,
+    /// how big the spell was drawn, before it was scaled to `SIZE`.
+    pub size: Option<SizeClass>,
+    /// the `Dtw` template of single stroke, `time_warped` spells.
+    pub warped: Option<Box<NormedSpell>>,
+    /// how the spell was drawn over time, if it was timed.
+    pub tempo: Option<Box<Tempo>>,
+}
+
+struct Entry<T> {
+    id: SpellId,
+    options: SpellOptions,
+    template: Normalized<T>,
+}
+
+/// a recognizer that compares a cast spell to every learned example using `M`. multistroke spells
+/// are compared with $P whatever `M` is, since the other matchers need a single stroke.
+pub struct TemplateRecognizer<M: Matcher> {
+    matcher: M,
+    entries: Vec<Entry<M::Template>>,
+    /// the single stroke entries cut short at each of `PREFIX_FRACTIONS`.
+    prefixes: Vec<Entry<M::Template>>,
+}
+
+impl<M: Matcher> TemplateRecognizer<M> {
+    pub fn new(matcher: M) -> Self {
+        Self {
+            matcher,
+            entries: Vec::new(),
+            prefixes: Vec::new(),
+        }
+    }
+
+    /// scores `spell` against `entries`, keeping the best score of each spell.
+    async fn rank<'a>(
+        &'a self,
+        spell: &Spell,
+        timing: &Timing,
+        entries: impl Iterator<Item = &'a Entry<M::Template>> + Clone,
+    ) -> Result<Vec<(SpellId, f32)>, RecognizeError> {
+        let warp = entries.clone().any(|entry| entry.options.time_warped);
+        let cast_spell = self.normalize_cast(spell, timing, warp)?;
+        let mirrored = if entries.clone().any(|entry| entry.options.mirror_invariant) {
+            [
+                self.normalize_cast(&mirrored(spell, true), timing, warp)?,
+                self.normalize_cast(&mirrored(spell, false), timing, warp)?,
+            ]
+            .into()
+        } else {
+            Vec::new()
+        };
+        let mut ranked: Vec<(SpellId, f32)> = Vec::new();
+
+        for (i, entry) in entries.enumerate() {
+            trace!("comparing to spell {}, template {i}", entry.id);
+            let mut s = self.score(&cast_spell, &entry.template, &entry.options);
+
+            if entry.options.mirror_invariant {
+                for cast_spell in mirrored.iter() {
+                    s = s.max(self.score(cast_spell, &entry.template, &entry.options));
+                }
+            }
+
+            yield_now().await;
+
+            match ranked.iter_mut().find(|(id, _)| *id == entry.id) {
+                Some((_, b)) if s > *b => *b = s,
+                Some(_) => {}
+                None => ranked.push((entry.id, s)),
+            }
+        }
+
+        ranked.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+        Ok(ranked)
+    }
+
+    /// normalizes a cast spell, along with its `Dtw` template if `warp`.
+    fn normalize_cast(
+        &self,
+        spell: &Spell,
+        timing: &Timing,
+        warp: bool,
+    ) -> Result<Normalized<M::Template>, RecognizeError> {
+        let mut cast_spell = self.normalize(spell, timing)?;
+
+        if warp && cast_spell.cloud.is_none() {
+            cast_spell.warped = Some(Box::new(Dtw.normalize(spell)?));
+        }
+
+        Ok(cast_spell)
+    }
+
+    /// normalizes a learned example, along with its reversed path & its `Dtw` template if
+    /// `options` ask for them.
+    fn normalize_template(
+        &self,
+        spell: &Spell,
+        timing: &Timing,
+        options: &SpellOptions,
+    ) -> Result<Normalized<M::Template>, RecognizeError> {
+        let mut template = self.normalize_cast(spell, timing, options.time_warped)?;
+
+        if !options.direction_sensitive && template.cloud.is_none() {
+            template.reversed = Some(Box::new(self.matcher.normalize(&reversed(spell))?));
+        }
+
+        Ok(template)
+    }
+
+    fn score(
+        &self,
+        cast_spell: &Normalized<M::Template>,
+        template: &Normalized<M::Template>,
+        options: &SpellOptions,
+    ) -> f32 {
+        if (options.position_aware && cast_spell.region != template.region)
+            || (options.size_aware && cast_spell.size != template.size)
+        {
+            return 0.0;
+        }
+
+        let s = self.shape_score(cast_spell, template, options);
+
+        if !options.timed {
+            return s;
+        }
+
+        match (&cast_spell.tempo, &template.tempo) {
+            (Some(cast_tempo), Some(tempo)) => s * cast_tempo.similarity(tempo),
+            // an untimed spell, or a prefix, could have been drawn at any speed.
+            _ => 0.0,
+        }
+    }
+
+    /// how well the shapes match, whatever the timing & position.
+    fn shape_score(
+        &self,
+        cast_spell: &Normalized<M::Template>,
+        template: &Normalized<M::Template>,
+        options: &SpellOptions,
+    ) -> f32 {
+        match (&cast_spell.cloud, &template.cloud) {
+            (Some(cast_cloud), Some(cloud)) => cloud_score(cast_cloud, cloud),
+            (None, None) if options.time_warped => {
+                let (Some(cast_warped), Some(warped)) = (&cast_spell.warped, &template.warped)
+                else {
+                    // the prefixes of a spell are not warped.
+                    return self
+                        .matcher
+                        .score(&cast_spell.template, &template.template, options);
+                };
+                let s = Dtw.score(cast_warped, warped, options);
+
+                if options.direction_sensitive {
+                    s
+                } else {
+                    // the stroke is not rotated to normalize it, so its reversed template is the
+                    // same points in reverse.
+                    let mut reversed = **warped;
+                    reversed.reverse();
+
+                    s.max(Dtw.score(cast_warped, &reversed, options))
+                }
+            }
+            (None, None) => {
+                let s = self
+                    .matcher
+                    .score(&cast_spell.template, &template.template, options);
+
+                match &template.reversed {
+                    Some(reversed) => {
+                        s.max(self.matcher.score(&cast_spell.template, reversed, options))
+                    }
+                    None => s,
+                }
+            }
+            // a multistroke spell is never the same spell as a single stroke one.
+            _ => 0.0,
+        }
+    }
+}
+
+impl<M: Matcher> Recognizer for TemplateRecognizer<M> {
+    type Template = Normalized<M::Template>;
+
+    fn normalize(&self, spell: &Spell, timing: &Timing) -> Result<Self::Template, RecognizeError> {
+        let cloud = if spell.len() > 1 {
+            Some(Box::new(process_cloud(spell)?))
+        } else {
+            None
+        };
+
+        Ok(Normalized {
+            template: self.matcher.normalize(spell)?,
+            cloud,
+            reversed: None,
+            region: PadRegion::of(spell),
+            size: SizeClass::of(spell),
+            warped: None,
+            tempo: Tempo::of(spell, timing).map(Box::new),
+        })
+    }
+
+    fn add_template(
+        &mut self,
+        id: SpellId,
+        spell: &Spell,
+        timing: &Timing,
+        options: SpellOptions,
+    ) -> Result<(), RecognizeError> {
+        let template = self.normalize_template(spell, timing, &options)?;
+
+        self.entries.push(Entry {
+            id,
+            options,
+            template,
+        });
+
+        if spell.len() == 1 {
+            let mut paths = Vec::from([spell.clone()]);
+
+            if !options.direction_sensitive {
+                paths.push(reversed(spell));
+            }
+
+            for path in paths.iter() {
+                for fraction in PREFIX_FRACTIONS {
+                    // a prefix too short to normalize is no use anyway.
+                    if let Ok(template) = self.normalize(&prefix(path, fraction), &Timing::new()) {
+                        self.prefixes.push(Entry {
+                            id,
+                            options,
+                            template,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn classify(
+        &self,
+        spell: &Spell,
+        timing: &Timing,
+    ) -> Result<Vec<(SpellId, f32)>, RecognizeError> {
+        if self.entries.is_empty() {
+            return Err(RecognizeError::EmptyCorpus);
+        }
+
+        self.rank(spell, timing, self.entries.iter()).await
+    }
+
+    async fn classify_prefix(&self, spell: &Spell) -> Result<Vec<(SpellId, f32)>, RecognizeError> {
+        if self.prefixes.is_empty() {
+            return Err(RecognizeError::EmptyCorpus);
+        }
+
+        let whole = self
+            .entries
+            .iter()
+            .filter(|entry| entry.template.cloud.is_none());
+
+        self.rank(spell, &Timing::new(), whole.chain(self.prefixes.iter()))
+            .await
+    }
+
+    fn remove_template(&mut self, id: SpellId) {
+        self.entries.retain(|entry| entry.id != id);
+        self.prefixes.retain(|entry| entry.id != id);
+    }
+
+    async fn example_scores(&self, id: SpellId) -> Vec<f32> {
+        let examples: Vec<&Entry<M::Template>> =
+            self.entries.iter().filter(|entry| entry.id == id).collect();
+        let mut scores = Vec::new();
+
+        for (i, a) in examples.iter().enumerate() {
+            for b in examples[i + 1..].iter() {
+                scores.push(self.score(&a.template, &b.template, &a.options));
+                yield_now().await;
+            }
+        }
+
+        scores
+    }
+}
+
+/// the recognizer picked at runtime with the `/backend` command.
+pub enum ActiveRecognizer {
+    DollarOne(TemplateRecognizer<DollarOne>),
+    Protractor(TemplateRecognizer<Protractor>),
+    DollarP(TemplateRecognizer<DollarP>),
+    ShortStraw(TemplateRecognizer<ShortStraw>),
+    Rubine(RubineRecognizer),
+}
+
+impl ActiveRecognizer {
+    pub fn new(backend: Backend) -> Self {
+        match backend {
+            Backend::DollarOne => Self::DollarOne(TemplateRecognizer::new(DollarOne)),
+            Backend::Protractor => Self::Protractor(TemplateRecognizer::new(Protractor)),
+            Backend::DollarP => Self::DollarP(TemplateRecognizer::new(DollarP)),
+            Backend::ShortStraw => Self::ShortStraw(TemplateRecognizer::new(ShortStraw)),
+            Backend::Rubine => Self::Rubine(RubineRecognizer::new()),
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self {
+            Self::DollarOne(_) => Backend::DollarOne,
+            Self::Protractor(_) => Backend::Protractor,
+            Self::DollarP(_) => Backend::DollarP,
+            Self::ShortStraw(_) => Backend::ShortStraw,
+            Self::Rubine(_) => Backend::Rubine,
+        }
+    }
+
+    pub fn add_template(
+        &mut self,
+        id: SpellId,
+        spell: &Spell,
+        timing: &Timing,
+        options: SpellOptions,
+    ) -> Result<(), RecognizeError> {
+        match self {
+            Self::DollarOne(r) => r.add_template(id, spell, timing, options),
+            Self::Protractor(r) => r.add_template(id, spell, timing, options),
+            Self::DollarP(r) => r.add_template(id, spell, timing, options),
+            Self::ShortStraw(r) => r.add_template(id, spell, timing, options),
+            Self::Rubine(r) => r.add_template(id, spell, timing, options),
+        }
+    }
+
+    pub async fn classify(
+        &self,
+        spell: &Spell,
+        timing: &Timing,
+    ) -> Result<Vec<(SpellId, f32)>, RecognizeError> {
+        match self {
+            Self::DollarOne(r) => r.classify(spell, timing).await,
+            Self::Protractor(r) => r.classify(spell, timing).await,
+            Self::DollarP(r) => r.classify(spell, timing).await,
+            Self::ShortStraw(r) => r.classify(spell, timing).await,
+            Self::Rubine(r) => r.classify(spell, timing).await,
+        }
+    }
+
+    pub async fn classify_prefix(
+        &self,
+        spell: &Spell,
+    ) -> Result<Vec<(SpellId, f32)>, RecognizeError> {
+        match self {
+            Self::DollarOne(r) => r.classify_prefix(spell).await,
+            Self::Protractor(r) => r.classify_prefix(spell).await,
+            Self::DollarP(r) => r.classify_prefix(spell).await,
+            Self::ShortStraw(r) => r.classify_prefix(spell).await,
+            Self::Rubine(r) => r.classify_prefix(spell).await,
+        }
+    }
+
+    pub fn remove_template(&mut self, id: SpellId) {
+        match self {
+            Self::DollarOne(r) => r.remove_template(id),
+            Self::Protractor(r) => r.remove_template(id),
+            Self::DollarP(r) => r.remove_template(id),
+            Self::ShortStraw(r) => r.remove_template(id),
+            Self::Rubine(r) => r.remove_template(id),
+        }
+    }
+
+    pub async fn example_scores(&self, id: SpellId) -> Vec<f32> {
+        match self {
+            Self::DollarOne(r) => r.example_scores(id).await,
+            Self::Protractor(r) => r.example_scores(id).await,
+            Self::DollarP(r) => r.example_scores(id).await,
+            Self::ShortStraw(r) => r.example_scores(id).await,
+            Self::Rubine(r) => r.example_scores(id).await,
+        }
+    }
+}
+
+/// a learned spell, made up of every example drawn for it while learning. the raw examples are
+/// kept, with their timing, so any backend can be trained on them.
+pub struct SpellClass {
+    pub examples: Vec<(Spell, Timing)>,
+    pub options: SpellOptions,
+    /// the lowest score a cast spell needs to count as this spell.
+    pub threshold: f32,
+    /// a threshold set by hand, kept instead of the one derived from the examples.
+    pub threshold_override: Option<f32>,
+    /// how many fingers the spell is drawn with, a two finger circle is not a one finger one.
+    pub fingers: u8,
+}
+
+impl SpellClass {
+    pub fn new(options: SpellOptions, fingers: u8) -> Self {
+        Self {
+            examples: Vec::new(),
+            options,
+            threshold: DEFAULT_THRESHOLD,
+            threshold_override: None,
+            fingers,
+        }
+    }
+}
+
+/// every learned spell, indexed by `SpellId`, & the recognizer trained on them.
+pub struct SpellBook {
+    pub spells: Vec<SpellClass>,
+    recognizer: ActiveRecognizer,
+    /// makes every spell mirror invariant, for a left-handed user of a shared spell book.
+    mirror_invariant: bool,
+}
+
+impl SpellBook {
+    pub fn new(backend: Backend) -> Self {
+        Self {
+            spells: Vec::new(),
+            recognizer: ActiveRecognizer::new(backend),
+            mirror_invariant: false,
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.recognizer.backend()
+    }
+
+    pub fn mirror_invariant(&self) -> bool {
+        self.mirror_invariant
+    }
+
+    /// adds an example to `spell`, or to a new spell with `options` if `None`, & re-derives the
+    /// spell's threshold from how closely its examples match each other, so a spell that is
+    /// always drawn the same way gets a stricter cutoff. the new spell is only created once its
+    /// first example is accepted. every example of a spell is drawn with the same `fingers`.
+    pub async fn learn(
+        &mut self,
+        spell: Option<SpellId>,
+        options: SpellOptions,
+        example: Spell,
+        timing: Timing,
+        fingers: u8,
+    ) -> Result<SpellId, RecognizeError> {
+        // checked before the recognizer is given a template it has no spell for.
+        if spell.is_some_and(|id| id >= self.spells.len()) {
+            return Err(RecognizeError::UnknownSpell);
+        }
+
+        let id = spell.unwrap_or(self.spells.len());
+
+        if self
+            .spells
+            .get(id)
+            .is_some_and(|spell| spell.fingers != fingers)
+        {
+            return Err(RecognizeError::WrongFingers);
+        }
+
+        let options = self.spells.get(id).map_or(options, |spell| spell.options);
+        self.recognizer
+            .add_template(id, &example, &timing, self.trained_options(options))?;
+
+        if id == self.spells.len() {
+            self.spells.push(SpellClass::new(options, fingers));
+        }
+
+        self.spells[id].examples.push((example, timing));
+        self.update_threshold(id).await;
+
+        Ok(id)
+    }
+
+    /// retrains a new recognizer on every learned example. thresholds are re-derived, since the
+    /// backends score differently.
+    pub async fn set_backend(&mut self, backend: Backend) {
+        self.recognizer = ActiveRecognizer::new(backend);
+        self.retrain().await;
+    }
+
+    /// makes every spell mirror invariant, or only those learned as such, without losing the
+    /// per-spell setting.
+    pub async fn set_mirror_invariant(&mut self, mirror_invariant: bool) {
+        self.mirror_invariant = mirror_invariant;
+        self.recognizer = ActiveRecognizer::new(self.backend());
+        self.retrain().await;
+    }
+
+    /// trains the (empty) recognizer on every learned example.
+    async fn retrain(&mut self) {
+        for (id, spell) in self.spells.iter().enumerate() {
+            let options = self.trained_options(spell.options);
+
+            for (example, timing) in spell.examples.iter() {
+                if let Err(e) = self.recognizer.add_template(id, example, timing, options) {
+                    error!("spell {id} could not be retrained: {e}");
+                }
+            }
+        }
+
+        for id in 0..self.spells.len() {
+            self.update_threshold(id).await;
+        }
+    }
+
+    /// a spell's options with the book wide settings applied.
+    fn trained_options(&self, options: SpellOptions) -> SpellOptions {
+        SpellOptions {
+            mirror_invariant: options.mirror_invariant || self.mirror_invariant,
+            ..options
+        }
+    }
+
+    /// sets the spell's threshold by hand, so it is no longer derived from its examples, returning
+    /// the threshold it replaced. `None` if there is no such spell.
+    pub fn set_threshold(&mut self, id: SpellId, threshold: f32) -> Option<f32> {
+        let spell = self.spells.get_mut(id)?;
+        let old = spell.threshold;
+        spell.threshold = threshold;
+        spell.threshold_override = Some(threshold);
+
+        Some(old)
+    }
+
+    /// forgets the spell's examples. its id is not reused, so other spells keep theirs.
+    pub fn forget(&mut self, id: SpellId) -> bool {
+        match self.spells.get_mut(id) {
+            Some(spell) => {
+                spell.examples.clear();
+                self.recognizer.remove_template(id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    async fn update_threshold(&mut self, id: SpellId) {
+        if let Some(threshold) = self.spells[id].threshold_override {
+            self.spells[id].threshold = threshold;
+            return;
+        }
+
+        let scores = self.recognizer.example_scores(id).await;
+
+        if scores.is_empty() {
+            self.spells[id].threshold = DEFAULT_THRESHOLD;
+            return;
+        }
+
+        let n = scores.len() as f32;
+        let mean = scores.iter().sum::<f32>() / n;
+        let variance = scores.iter().map(|s| (s - mean) * (s - mean)).sum::<f32>() / n;
+        let threshold =
+            (mean - THRESHOLD_STD_DEVS * variance.sqrt()).clamp(MIN_THRESHOLD, MAX_THRESHOLD);
+        debug!(
+            "examples of spell {id} score {mean} +/- {}, threshold is now {threshold}",
+            variance.sqrt()
+        );
+        self.spells[id].threshold = threshold;
+    }
+}
+
+/// the outcome of comparing a cast spell against every learned spell.
+#[derive(Clone, Copy, Debug)]
+pub struct Recognition {
+    pub spell: SpellId,
+    pub score: f32,
+    /// the best scoring spell other than `spell`, if more than one spell is learned.
+    pub runner_up: Option<(SpellId, f32)>,
+}
+
+impl Recognition {
+    /// builds a recognition from candidates sorted best first, as returned by
+    /// `spell_compare_top_k`. returns `None` if there are no candidates.
+    pub fn from_candidates(candidates: &[(SpellId, f32)]) -> Option<Self> {
+        let (spell, score) = *candidates.first()?;
+
+        Some(Self {
+            spell,
+            score,
+            runner_up: candidates.get(1).copied(),
+        })
+    }
+
+    /// true when the runner up scored within `margin` of the best match.
+    pub fn is_ambiguous(&self, margin: f32) -> bool {
+        self.runner_up
+            .is_some_and(|(_, runner_up)| self.score - runner_up < margin)
+    }
+
+    /// true when a recognition of an unfinished spell is sure enough to cast it before the
+    /// finger is lifted.
+    pub fn is_decisive(&self, threshold: f32) -> bool {
+        self.score > threshold.max(EARLY_THRESHOLD) && !self.is_ambiguous(EARLY_MARGIN)
+    }
+}
+
+// the $1 paper's phi is the golden ratio's conjugate, 0.5 * (-1 + sqrt(5)), not the golden ratio.
+const PHI: f32 = 0.618_034;
+pub const THETA: f32 = PI / 4.0;
+pub const NEG_THETA: f32 = -THETA;
+pub const THETA_DELTA: f32 = PI / 90.0;
+pub const N: usize = 64;
+const SIZE: f32 = 256.0;
+/// how far from point i to point i `Dtw` can match points up.
+const WARP_BAND: usize = N / 10;
+/// the fewest points a spell can be drawn with.
+pub const MIN_POINTS: usize = 5;
+/// strokes thinner than this, relative to their length, are scaled uniformly as 1D gestures.
+const ONE_D_RATIO: f32 = 0.3;
+/// used until a spell has at least two examples to compare.
+pub const DEFAULT_THRESHOLD: f32 = 0.6;
+const MIN_THRESHOLD: f32 = 0.5;
+const MAX_THRESHOLD: f32 = 0.95;
+const THRESHOLD_STD_DEVS: f32 = 2.0;
+/// how far ahead of the runner up the best match must score for it to be cast.
+pub const DEFAULT_MARGIN: f32 = 0.05;
+/// how far along each single stroke example, by path length, its prefix templates are cut.
+const PREFIX_FRACTIONS: [f32; 3] = [0.4, 0.6, 0.8];
+/// the lowest score an unfinished spell needs to be cast early, whatever its threshold.
+pub const EARLY_THRESHOLD: f32 = 0.85;
+/// how far ahead of the runner up an unfinished spell has to be to be cast early.
+pub const EARLY_MARGIN: f32 = 0.15;
+
+// async fn lerp_2d(p_1: (f32, f32), p_2: (f32, f32), fract: f32) -> (f32, f32) {
+//     let lerp = |start, end, t| start + t * (end - start);
+//
+//     let x = lerp(p_1.0, p_1.1, fract);
+//     let x = if x <= 1.0 { x } else { 1.0 };
+//
+//     let y = lerp(p_2.0, p_2.1, fract);
+//     let y = if y <= 1.0 { y } else { 1.0 };
+//
+//     (x, y)
+// }
+
+// STEP 1
+
+/// resamples the strokes to `N` points spaced evenly along them. the gap between two strokes is
+/// skipped, so a spell passed as a single joined stroke is resampled as one path.
+fn resample<S, P>(strokes: S) -> NormedSpell
+where
+    S: Iterator<Item = P> + Clone,
+    P: Iterator<Item = NormedPoint> + Clone,
+{
+    let cap_i = strokes.clone().map(path_length).sum::<f32>() / (N - 1) as f32;
+    let mut cap_d = 0.0;
+    let mut new_points = [(0.0, 0.0); N];
+    let mut len = 0;
+    let mut last = (0.0, 0.0);
+
+    for mut stroke in strokes {
+        let Some(mut a) = stroke.next() else {
+            continue;
+        };
+
+        if len == 0 {
+            new_points[0] = a;
+            len = 1;
+        }
+
+        for b in stroke {
+            let mut d = distance(a, b);
+
+            while cap_d + d >= cap_i && d > 0.0 && len < N {
+                let t = (cap_i - cap_d) / d;
+                let q = (a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1));
+                new_points[len] = q;
+                len += 1;
+                a = q;
+                d = distance(a, b);
+                cap_d = 0.0;
+            }
+
+            cap_d += d;
+            a = b;
+        }
+
+        last = a;
+    }
+
+    // rounding can leave the last point or two unplaced.
+    for p in new_points[len..].iter_mut() {
+        *p = last;
+    }
+
+    new_points
+}
+
+fn path_length(points: impl Iterator<Item = NormedPoint>) -> f32 {
+    let mut d = 0.0;
+    let mut points = points;
+
+    if let Some(mut a) = points.next() {
+        for b in points {
+            d += distance(a, b);
+            a = b;
+        }
+    }
+
+    d
+}
+
+fn distance(a: NormedPoint, b: NormedPoint) -> f32 {
+    let dx = a.0 - b.0;
+    let dy = a.1 - b.1;
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// the raw points of each stroke, as floats.
+fn strokes(
+    spell: &Spell,
+) -> impl Iterator<Item = impl Iterator<Item = NormedPoint> + Clone> + Clone {
+    spell
+        .iter()
+        .map(|stroke| stroke.iter().map(|(x, y)| (*x as f32, *y as f32)))
+}
+
+fn point_count(spell: &Spell) -> usize {
+    spell.iter().map(|stroke| stroke.len()).sum()
+}
+
+/// the spell flipped left to right if `horizontal`, else top to bottom, within its bounding box.
+pub(crate) fn mirrored(spell: &Spell, horizontal: bool) -> Spell {
+    let axis = |p: &Point| if horizontal { p.0 } else { p.1 };
+    let min = spell.iter().flatten().map(axis).min().unwrap_or_default();
+    let max = spell.iter().flatten().map(axis).max().unwrap_or_default();
+
+    spell
+        .iter()
+        .map(|stroke| {
+            stroke
+                .iter()
+                .map(|&(x, y)| {
+                    if horizontal {
+                        (max - (x - min), y)
+                    } else {
+                        (x, max - (y - min))
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// the first stroke of the spell, cut where `fraction` of its path length has been drawn.
+fn prefix(spell: &Spell, fraction: f32) -> Spell {
+    let Some(stroke) = spell.first() else {
+        return Vec::new();
+    };
+    let lengths: Vec<f32> = stroke
+        .windows(2)
+        .map(|pair| {
+            distance(
+                (pair[0].0 as f32, pair[0].1 as f32),
+                (pair[1].0 as f32, pair[1].1 as f32),
+            )
+        })
+        .collect();
+    let cut = fraction * lengths.iter().sum::<f32>();
+    let mut drawn = 0.0;
+    let end = lengths
+        .iter()
+        .position(|d| {
+            drawn += d;
+            drawn >= cut
+        })
+        .map_or(stroke.len(), |i| i + 2);
+
+    Vec::from([stroke[..end].to_vec()])
+}
+
+/// the spell drawn from its last point back to its first.
+pub(crate) fn reversed(spell: &Spell) -> Spell {
+    spell
+        .iter()
+        .rev()
+        .map(|stroke| stroke.iter().rev().copied().collect())
+        .collect()
+}
+
+// STEP 2
+
+fn indicative_angle(points: &NormedSpell) -> f32 {
+    let c = centroid(points);
+
+    (c.1 - points[0].1).atan2(c.0 - points[0].0)
+}
+
+fn rotate(p: NormedPoint, c: NormedPoint, cos: f32, sin: f32) -> NormedPoint {
+    let qx = (p.0 - c.0) * cos - (p.1 - c.1) * sin + c.0;
+    let qy = (p.0 - c.0) * sin + (p.1 - c.1) * cos + c.1;
+
+    (qx, qy)
+}
+
+fn rotate_by(points: &NormedSpell, angle: f32) -> NormedSpell {
+    let c = centroid(points);
+    let (sin, cos) = angle.sin_cos();
+
+    points.map(|p| rotate(p, c, cos, sin))
+}
+
+// STEP 3
+
+fn scale_to(points: &mut [NormedPoint], size: f32) -> Result<(), RecognizeError> {
+    let (w, h) = bounding_box(points);
+    let (long, short) = (w.max(h), w.min(h));
+
+    if long <= f32::EPSILON {
+        return Err(RecognizeError::Degenerate);
+    }
+
+    // a line has no height (or width) to stretch, so it keeps its aspect ratio instead.
+    let (scale_x, scale_y) = if short / long <= ONE_D_RATIO {
+        (size / long, size / long)
+    } else {
+        (size / w, size / h)
+    };
+
+    for p in points.iter_mut() {
+        p.0 *= scale_x;
+        p.1 *= scale_y;
+    }
+
+    Ok(())
+}
+
+fn translate_to(points: &mut [NormedPoint], k: NormedPoint) {
+    let c = centroid(points);
+    let dx = k.0 - c.0;
+    let dy = k.1 - c.1;
+
+    for p in points.iter_mut() {
+        p.0 += dx;
+        p.1 += dy;
+    }
+}
+
+fn bounding_box(spell: &[NormedPoint]) -> (f32, f32) {
+    let (mut min_x, mut min_y) = (f32::INFINITY, f32::INFINITY);
+    let (mut max_x, mut max_y) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+
+    for (x, y) in spell.iter() {
+        min_x = min_x.min(*x);
+        min_y = min_y.min(*y);
+        max_x = max_x.max(*x);
+        max_y = max_y.max(*y);
+    }
+
+    (max_x - min_x, max_y - min_y)
+}
+
+fn centroid(points: &[NormedPoint]) -> NormedPoint {
+    let n = points.len() as f32;
+    let x: f32 = points.iter().map(|(x, _)| *x).sum();
+    let y: f32 = points.iter().map(|(_, y)| *y).sum();
+
+    (x / n, y / n)
+}
+
+// STEP 4
+
+fn score(d: f32, size: f32) -> f32 {
+    1.0 - d / (0.5 * (2.0 * size * size).sqrt())
+}
+
+/// the path distance at the best angle, with the points matched up by `warped_distance` if `warp`.
+fn example_distance(
+    cast_spell: &NormedSpell,
+    template: &NormedSpell,
+    options: &SpellOptions,
+    warp: bool,
+) -> f32 {
+    if options.rotation_sensitive {
+        distance_at_best_angle(cast_spell, template, warp)
+    } else {
+        let angle = indicative_angle(template) - indicative_angle(cast_spell);
+        let aligned = rotate_by(cast_spell, angle);
+
+        distance_at_best_angle(&aligned, template, warp)
+    }
+}
+
+fn distance_at_best_angle(cast_spell: &NormedSpell, template: &NormedSpell, warp: bool) -> f32 {
+    let c = centroid(cast_spell);
+    let mut x1 = PHI * NEG_THETA + (1.0 - PHI) * THETA;
+    let mut f1 = distance_at_angle(cast_spell, template, c, x1, warp);
+    let mut x2 = (1.0 - PHI) * NEG_THETA + PHI * THETA;
+    let mut f2 = distance_at_angle(cast_spell, template, c, x2, warp);
+    let mut a = NEG_THETA;
+    let mut b = THETA;
+
+    while (b - a).abs() > THETA_DELTA {
+        if f1 < f2 {
+            b = x2;
+            x2 = x1;
+            f2 = f1;
+            x1 = PHI * a + (1. - PHI) * b;
+            f1 = distance_at_angle(cast_spell, template, c, x1, warp);
+        } else {
+            a = x1;
+            x1 = x2;
+            f1 = f2;
+            x2 = (1.0 - PHI) * a + PHI * b;
+            f2 = distance_at_angle(cast_spell, template, c, x2, warp);
+        }
+    }
+
+    f1.min(f2)
+}
+
+/// the path distance with the cast spell turned by `angle` around `c`, rotating each point as it
+/// is compared instead of building a rotated copy.
+fn distance_at_angle(
+    cast_spell: &NormedSpell,
+    template: &NormedSpell,
+    c: NormedPoint,
+    angle: f32,
+    warp: bool,
+) -> f32 {
+    let (sin, cos) = angle.sin_cos();
+    let d: f32 = if warp {
+        let rotated = cast_spell.map(|p| rotate(p, c, cos, sin));
+
+        warped_distance(&rotated, template)
+    } else {
+        cast_spell
+            .iter()
+            .zip(template.iter())
+            .map(|(p, t)| distance(rotate(*p, c, cos, sin), *t))
+            .sum()
+    };
+
+    d / N as f32
+}
+
+/// the sum of the distances between matched up points, along the cheapest path through the
+/// points of both spells that stays within `WARP_BAND` points of matching point i to point i.
+/// never more than the path distance, which is one of the paths.
+fn warped_distance(cast_spell: &NormedSpell, template: &NormedSpell) -> f32 {
+    let mut previous = [f32::INFINITY; N];
+    let mut current = [f32::INFINITY; N];
+
+    for (i, p) in cast_spell.iter().enumerate() {
+        current.fill(f32::INFINITY);
+
+        for j in i.saturating_sub(WARP_BAND)..=(i + WARP_BAND).min(N - 1) {
+            let cheapest = match (i, j) {
+                (0, 0) => 0.0,
+                (0, _) => current[j - 1],
+                (_, 0) => previous[j],
+                _ => previous[j].min(previous[j - 1]).min(current[j - 1]),
+            };
+
+            current[j] = cheapest + distance(*p, template[j]);
+        }
+
+        core::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[N - 1]
+}
+
+// Protractor
+
+fn vectorize(points: &NormedSpell) -> Vector {
+    let magnitude = points
+        .iter()
+        .map(|(x, y)| x * x + y * y)
+        .sum::<f32>()
+        .sqrt();
+    let mut vector = [0.0; 2 * N];
+
+    for (v, (x, y)) in vector.chunks_exact_mut(2).zip(points.iter()) {
+        v[0] = x / magnitude;
+        v[1] = y / magnitude;
+    }
+
+    vector
+}
+
+/// the angle between the two vectors once the cast spell is turned to best match the template.
+fn optimal_cosine_distance(cast_spell: &Vector, template: &Vector, options: &SpellOptions) -> f32 {
+    let mut a = 0.0;
+    let mut b = 0.0;
+
+    for (t, c) in template.chunks_exact(2).zip(cast_spell.chunks_exact(2)) {
+        a += t[0] * c[0] + t[1] * c[1];
+        b += t[0] * c[1] - t[1] * c[0];
+    }
+
+    let angle = if options.rotation_sensitive {
+        b.atan2(a).clamp(NEG_THETA, THETA)
+    } else {
+        b.atan2(a)
+    };
+    let similarity = a * angle.cos() + b * angle.sin();
+
+    similarity.clamp(-1.0, 1.0).acos()
+}
+
+// $P
+
+/// scales the cloud uniformly to fit a unit box & moves its centroid to the origin.
+fn normalize_cloud(points: &mut NormedSpell) -> Result<(), RecognizeError> {
+    let (w, h) = bounding_box(points);
+    let scale = w.max(h);
+
+    if scale <= f32::EPSILON {
+        return Err(RecognizeError::Degenerate);
+    }
+
+    let c = centroid(points);
+
+    for p in points.iter_mut() {
+        *p = ((p.0 - c.0) / scale, (p.1 - c.1) / scale);
+    }
+
+    Ok(())
+}
+
+fn process_cloud(spell: &Spell) -> Result<NormedSpell, RecognizeError> {
+    if point_count(spell) < MIN_POINTS {
+        return Err(RecognizeError::TooShort);
+    }
+
+    let mut points = resample(strokes(spell));
+    normalize_cloud(&mut points)?;
+
+    Ok(points)
+}
+
+/// greedily matches each point of the cast cloud to its nearest unmatched template point,
+/// starting from several points & in both directions, & scores the cheapest matching. the
+/// weighted distance is averaged over the weights, so it is scored on the same scale as $1's.
+fn cloud_score(cast_spell: &NormedSpell, template: &NormedSpell) -> f32 {
+    let step = (N as f32).sqrt().floor() as usize;
+    let mut min = f32::INFINITY;
+
+    for start in (0..N).step_by(step) {
+        let d_1 = cloud_distance(cast_spell, template, start);
+        let d_2 = cloud_distance(template, cast_spell, start);
+        min = min.min(d_1).min(d_2);
+    }
+
+    // the weights of `cloud_distance` run from 1 down to 1/N.
+    let weights = (N + 1) as f32 / 2.0;
+
+    score(min / weights, 1.0).max(0.0)
+}
+
+fn cloud_distance(points: &NormedSpell, template: &NormedSpell, start: usize) -> f32 {
+    let mut matched = [false; N];
+    let mut sum = 0.0;
+    let mut i = start;
+
+    loop {
+        let mut min = f32::INFINITY;
+        let mut index = 0;
+
+        for (j, _) in matched.iter().enumerate().filter(|(_, matched)| !**matched) {
+            let d = distance(points[i], template[j]);
+
+            if d < min {
+                min = d;
+                index = j;
+            }
+        }
+
+        matched[index] = true;
+        let weight = 1.0 - ((i + N - start) % N) as f32 / N as f32;
+        sum += weight * min;
+        i = (i + 1) % N;
+
+        if i == start {
+            break;
+        }
+    }
+
+    sum
+}
+
+// Entry Points
+
+/// normalizes the spell for $1, with its strokes joined into one path.
+pub fn process_stroke(spell: &Spell) -> Result<NormedSpell, RecognizeError> {
+    if point_count(spell) < MIN_POINTS {
+        return Err(RecognizeError::TooShort);
+    }
+
+    if path_length(strokes(spell).flatten()) <= f32::EPSILON {
+        return Err(RecognizeError::Degenerate);
+    }
+
+    // Step 1
+    let mut points = resample(core::iter::once(strokes(spell).flatten()));
+    // Step 3 (skipping rotation)
+    scale_to(&mut points, SIZE)?;
+    translate_to(&mut points, (0., 0.));
+
+    Ok(points)
+}
+
+pub async fn spell_compare(
+    cast_spell: &Spell,
+    timing: &Timing,
+    fingers: u8,
+    spell_book: &SpellBook,
+) -> Result<Recognition, RecognizeError> {
+    let candidates = spell_compare_top_k(cast_spell, timing, fingers, spell_book, 2).await?;
+
+    Recognition::from_candidates(&candidates).ok_or(RecognizeError::EmptyCorpus)
+}
+
+/// the `k` best matching spells drawn with `fingers` & their scores, sorted best first.
+pub async fn spell_compare_top_k(
+    cast_spell: &Spell,
+    timing: &Timing,
+    fingers: u8,
+    spell_book: &SpellBook,
+    k: usize,
+) -> Result<Vec<(SpellId, f32)>, RecognizeError> {
+    info!(
+        "comparing {fingers} finger spell to {} learned spells using {:?}",
+        spell_book.spells.len(),
+        spell_book.backend()
+    );
+    let mut candidates = spell_book.recognizer.classify(cast_spell, timing).await?;
+    candidates.retain(|(id, _)| spell_book.spells[*id].fingers == fingers);
+    candidates.truncate(k);
+
+    Ok(candidates)
+}
+
+/// recognizes a single stroke spell that is still being drawn, see `Recognition::is_decisive`.
+pub async fn spell_compare_prefix(
+    partial_spell: &Spell,
+    fingers: u8,
+    spell_book: &SpellBook,
+) -> Result<Recognition, RecognizeError> {
+    let mut candidates = spell_book.recognizer.classify_prefix(partial_spell).await?;
+    candidates.retain(|(id, _)| spell_book.spells[*id].fingers == fingers);
+
+    Recognition::from_candidates(&candidates).ok_or(RecognizeError::EmptyCorpus)
+}
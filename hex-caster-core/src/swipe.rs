@@ -0,0 +1,99 @@
+use core::{f32::consts::FRAC_PI_4, fmt, str::FromStr};
+
+// unused when the tests pull in `std`, as in `spell_compare`.
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use num_traits::Float;
+
+use crate::Spell;
+
+/// the shortest a stroke can be, end to end in pad units, to be a swipe.
+pub const MIN_SWIPE: f32 = 150.0;
+/// how straight a stroke has to be to be a swipe, as the distance between its ends over the
+/// length of its path.
+pub const MIN_STRAIGHTNESS: f32 = 0.9;
+
+/// a straight stroke in one of 8 directions, recognized without being learned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Swipe {
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+    Up,
+    UpRight,
+}
+
+impl Swipe {
+    /// clockwise from right, as the pad's y grows downwards.
+    const DIRECTIONS: [Self; 8] = [
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+        Self::Up,
+        Self::UpRight,
+    ];
+
+    /// the direction of the spell if it is a single, long enough & straight enough stroke.
+    pub fn classify(spell: &Spell) -> Option<Self> {
+        let [stroke] = spell.as_slice() else {
+            return None;
+        };
+        let to_f32 = |p: &(u16, u16)| (p.0 as f32, p.1 as f32);
+        let (first, last) = (to_f32(stroke.first()?), to_f32(stroke.last()?));
+        let (dx, dy) = (last.0 - first.0, last.1 - first.1);
+        let chord = (dx * dx + dy * dy).sqrt();
+        let path = stroke
+            .windows(2)
+            .map(|pair| {
+                let (a, b) = (to_f32(&pair[0]), to_f32(&pair[1]));
+                ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+            })
+            .sum::<f32>();
+
+        if chord < MIN_SWIPE || chord < MIN_STRAIGHTNESS * path {
+            return None;
+        }
+
+        let sector = (dy.atan2(dx) / FRAC_PI_4).round() as i32;
+
+        Some(Self::DIRECTIONS[sector.rem_euclid(8) as usize])
+    }
+}
+
+impl fmt::Display for Swipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Right => write!(f, "swipe-right"),
+            Self::DownRight => write!(f, "swipe-down-right"),
+            Self::Down => write!(f, "swipe-down"),
+            Self::DownLeft => write!(f, "swipe-down-left"),
+            Self::Left => write!(f, "swipe-left"),
+            Self::UpLeft => write!(f, "swipe-up-left"),
+            Self::Up => write!(f, "swipe-up"),
+            Self::UpRight => write!(f, "swipe-up-right"),
+        }
+    }
+}
+
+impl FromStr for Swipe {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "swipe-right" => Ok(Self::Right),
+            "swipe-down-right" => Ok(Self::DownRight),
+            "swipe-down" => Ok(Self::Down),
+            "swipe-down-left" => Ok(Self::DownLeft),
+            "swipe-left" => Ok(Self::Left),
+            "swipe-up-left" => Ok(Self::UpLeft),
+            "swipe-up" => Ok(Self::Up),
+            "swipe-up-right" => Ok(Self::UpRight),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use core::{fmt, str::FromStr};
+
+use embassy_time::Duration;
+
+use crate::{Spell, Stroke};
+
+/// the longest a touch can last & still be a tap.
+pub const TAP_TIME: Duration = Duration::from_millis(200);
+/// the shortest a touch can last to be a long press.
+pub const LONG_PRESS: Duration = Duration::from_millis(600);
+/// how far a touch can wander, in pad units, & still be a tap rather than a stroke.
+pub const TAP_SLOP: u16 = 20;
+
+/// a gesture made of touches rather than strokes, recognized before any shape is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tap {
+    Single,
+    Double,
+    Triple,
+    LongPress,
+}
+
+impl Tap {
+    /// classifies the spell as a tap if every stroke is a touch that barely moved. `durations`
+    /// holds how long each stroke was touching the pad.
+    pub fn classify(spell: &Spell, durations: &[Duration]) -> Option<Self> {
+        if spell.is_empty() || spell.len() != durations.len() || !spell.iter().all(is_touch) {
+            return None;
+        }
+
+        if spell.len() == 1 && durations[0] >= LONG_PRESS {
+            return Some(Self::LongPress);
+        }
+
+        if durations.iter().any(|duration| *duration > TAP_TIME) {
+            return None;
+        }
+
+        match spell.len() {
+            1 => Some(Self::Single),
+            2 => Some(Self::Double),
+            3 => Some(Self::Triple),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Tap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Single => write!(f, "tap"),
+            Self::Double => write!(f, "double-tap"),
+            Self::Triple => write!(f, "triple-tap"),
+            Self::LongPress => write!(f, "long-press"),
+        }
+    }
+}
+
+impl FromStr for Tap {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tap" => Ok(Self::Single),
+            "double-tap" => Ok(Self::Double),
+            "triple-tap" => Ok(Self::Triple),
+            "long-press" => Ok(Self::LongPress),
+            _ => Err(()),
+        }
+    }
+}
+
+/// true when the stroke never left `TAP_SLOP` of where it started (a finger held still jitters a
+/// little, & a light touch can be just a point or two).
+fn is_touch(stroke: &Stroke) -> bool {
+    let Some(&(x, y)) = stroke.first() else {
+        return false;
+    };
+
+    stroke
+        .iter()
+        .all(|p| p.0.abs_diff(x) <= TAP_SLOP && p.1.abs_diff(y) <= TAP_SLOP)
+}
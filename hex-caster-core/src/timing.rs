@@ -0,0 +1,142 @@
+use alloc::vec::Vec;
+use embassy_time::Duration;
+// unused when the tests pull in `std`, as in `spell_compare`.
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use num_traits::Float;
+
+use crate::{Spell, Timing};
+
+/// how many speeds make up a `Tempo`'s speed profile.
+pub const PROFILE_SAMPLES: usize = 16;
+/// how many times longer, or shorter, than one of its examples a `timed` spell can take to draw.
+pub const MAX_DURATION_RATIO: f32 = 2.0;
+
+/// how a spell was drawn over time, for spells learned as `timed`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tempo {
+    /// how long the finger was drawing, in seconds. the gaps between strokes do not count.
+    pub duration: f32,
+    /// the speed at `PROFILE_SAMPLES` even steps of the duration, relative to the average speed,
+    /// so a flick that speeds up & slows down is not a drag at a steady pace.
+    pub profile: [f32; PROFILE_SAMPLES],
+    /// the fastest of the profile's speeds, in pad units per second.
+    pub max_speed: f32,
+}
+
+impl Tempo {
+    /// `None` if the spell was not timed, or took no time or went nowhere.
+    pub fn of(spell: &Spell, timing: &Timing) -> Option<Self> {
+        let samples = samples(spell, timing)?;
+        let (duration, length) = samples.last().map(|sample| (sample.time, sample.length))?;
+
+        if duration <= 0.0 || length <= 0.0 {
+            return None;
+        }
+
+        let step = duration / PROFILE_SAMPLES as f32;
+        let lengths: Vec<f32> = (0..=PROFILE_SAMPLES)
+            .map(|i| at(&samples, i as f32 * step).length)
+            .collect();
+        let speeds: Vec<f32> = lengths.windows(2).map(|l| (l[1] - l[0]) / step).collect();
+        let mean_speed = length / duration;
+
+        Some(Self {
+            duration,
+            profile: core::array::from_fn(|i| speeds[i] / mean_speed),
+            max_speed: speeds.iter().copied().fold(0.0, f32::max),
+        })
+    }
+
+    /// how alike two tempos are, from 0 to 1. 0 if one took more than `MAX_DURATION_RATIO` times
+    /// as long as the other, otherwise how closely their speed profiles match.
+    pub fn similarity(&self, other: &Self) -> f32 {
+        let ratio = (self.duration / other.duration).max(other.duration / self.duration);
+
+        if ratio > MAX_DURATION_RATIO {
+            return 0.0;
+        }
+
+        let difference = self
+            .profile
+            .iter()
+            .zip(other.profile.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f32>()
+            / PROFILE_SAMPLES as f32;
+
+        (1.0 - difference / 2.0).max(0.0)
+    }
+}
+
+/// how long the finger had been drawing & how far it had gone when a point of a spell was drawn.
+#[derive(Clone, Copy)]
+struct Sample {
+    time: f32,
+    length: f32,
+}
+
+/// the spell's points in order, with the gaps between strokes taken out of both the time & the
+/// distance. `None` if `timing` is not the same shape as `spell`.
+fn samples(spell: &Spell, timing: &Timing) -> Option<Vec<Sample>> {
+    if spell.is_empty()
+        || spell.len() != timing.len()
+        || spell
+            .iter()
+            .zip(timing.iter())
+            .any(|(stroke, times)| stroke.is_empty() || stroke.len() != times.len())
+    {
+        return None;
+    }
+
+    let mut samples: Vec<Sample> = Vec::new();
+
+    for (stroke, times) in spell.iter().zip(timing.iter()) {
+        // the stroke starts where the last one left off, in time & distance.
+        let (offset, mut length) = samples
+            .last()
+            .map_or((0.0, 0.0), |last| (last.time, last.length));
+        let mut previous: Option<(f32, f32)> = None;
+
+        for (point, t) in stroke.iter().zip(times.iter()) {
+            let point = (point.0 as f32, point.1 as f32);
+
+            if let Some(previous) = previous {
+                length += (point.0 - previous.0).hypot(point.1 - previous.1);
+            }
+
+            samples.push(Sample {
+                time: offset + seconds(t.checked_sub(times[0]).unwrap_or_default()),
+                length,
+            });
+            previous = Some(point);
+        }
+    }
+
+    Some(samples)
+}
+
+fn seconds(duration: Duration) -> f32 {
+    duration.as_micros() as f32 / 1_000_000.0
+}
+
+/// the sample at `time`, interpolated between the two either side of it.
+fn at(samples: &[Sample], time: f32) -> Sample {
+    let i = samples.partition_point(|sample| sample.time < time);
+
+    match (
+        i.checked_sub(1).map(|i| samples[i]),
+        samples.get(i).copied(),
+    ) {
+        (Some(a), Some(b)) if b.time > a.time => {
+            let t = (time - a.time) / (b.time - a.time);
+
+            Sample {
+                time,
+                length: a.length + t * (b.length - a.length),
+            }
+        }
+        (_, Some(b)) => b,
+        (Some(a), None) => a,
+        (None, None) => Sample { time, length: 0.0 },
+    }
+}
@@ -0,0 +1,69 @@
+use crate::Point;
+
+/// the length of the pad's touch report: a 2 byte length, the report id, 5 contacts, the scan
+/// time, the contact count & the buttons.
+pub const REPORT_SIZE: usize = 37;
+/// the report id of touch reports.
+pub const TOUCH_REPORT_ID: u8 = 1;
+/// how many contacts a report has room for.
+pub const MAX_CONTACTS: usize = 5;
+
+const CONTACTS_START: usize = 3;
+const CONTACT_SIZE: usize = 6;
+const CONTACT_COUNT: usize = 35;
+
+/// one finger on the pad.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Contact {
+    pub id: u8,
+    /// the confidence & tip switch bits.
+    pub flags: u8,
+    pub point: Point,
+}
+
+/// a touch report, parsed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TouchReport {
+    contacts: [Contact; MAX_CONTACTS],
+    count: u8,
+}
+
+impl TouchReport {
+    /// `None` if `report` is not a whole touch report.
+    pub fn parse(report: &[u8]) -> Option<Self> {
+        if report.len() < REPORT_SIZE || report[2] != TOUCH_REPORT_ID {
+            return None;
+        }
+
+        let mut contacts = [Contact::default(); MAX_CONTACTS];
+
+        for (contact, bytes) in contacts.iter_mut().zip(
+            report[CONTACTS_START..CONTACTS_START + MAX_CONTACTS * CONTACT_SIZE]
+                .chunks_exact(CONTACT_SIZE),
+        ) {
+            *contact = Contact {
+                flags: bytes[0],
+                id: bytes[1],
+                point: (
+                    u16::from_le_bytes([bytes[2], bytes[3]]),
+                    u16::from_le_bytes([bytes[4], bytes[5]]),
+                ),
+            };
+        }
+
+        Some(Self {
+            contacts,
+            count: report[CONTACT_COUNT].min(MAX_CONTACTS as u8),
+        })
+    }
+
+    /// the fingers on the pad.
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts[..self.count as usize]
+    }
+
+    /// the first contact's position, which the pad zeroes once every finger is lifted.
+    pub fn point(&self) -> Point {
+        self.contacts[0].point
+    }
+}
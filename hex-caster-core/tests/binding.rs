@@ -0,0 +1,61 @@
+use hex_caster_core::{
+    binding::{Bindings, DEFAULT_SHORTCUT, Shortcut, Trigger},
+    swipe::Swipe,
+    tap::Tap,
+};
+
+#[test]
+fn shortcuts_parse() {
+    assert_eq!("gui+enter".parse(), Ok(DEFAULT_SHORTCUT));
+    assert_eq!(
+        "ctrl+alt+t".parse(),
+        Ok(Shortcut {
+            modifier: 0x05,
+            keycodes: [0x17, 0, 0, 0, 0, 0],
+        })
+    );
+    assert_eq!(
+        "shift+f5+1+0".parse(),
+        Ok(Shortcut {
+            modifier: 0x02,
+            keycodes: [0x3e, 0x1e, 0x27, 0, 0, 0],
+        })
+    );
+    assert!("ctrl+nope".parse::<Shortcut>().is_err());
+    assert!("f13".parse::<Shortcut>().is_err());
+    assert!("a+b+c+d+e+f+g".parse::<Shortcut>().is_err());
+    assert!("".parse::<Shortcut>().is_err());
+}
+
+#[test]
+fn triggers_parse() {
+    assert_eq!("3".parse(), Ok(Trigger::Spell(3)));
+    assert_eq!("3-held".parse(), Ok(Trigger::Held(3)));
+    assert_eq!("double-tap".parse(), Ok(Trigger::Tap(Tap::Double)));
+    assert_eq!("swipe-up".parse(), Ok(Trigger::Swipe(Swipe::Up, 1)));
+    assert_eq!(
+        "3-finger-swipe-down-left".parse(),
+        Ok(Trigger::Swipe(Swipe::DownLeft, 3))
+    );
+    assert!("x-finger-swipe-up".parse::<Trigger>().is_err());
+    assert!("circle".parse::<Trigger>().is_err());
+    assert!("circle-held".parse::<Trigger>().is_err());
+}
+
+#[test]
+fn rebinding_replaces() {
+    let mut bindings = Bindings::default();
+    let tap = Trigger::Tap(Tap::Single);
+
+    assert_eq!(bindings.get(tap), None);
+
+    bindings.bind(tap, DEFAULT_SHORTCUT);
+    bindings.bind(tap, "esc".parse().unwrap());
+    bindings.bind(Trigger::Spell(0), DEFAULT_SHORTCUT);
+
+    assert_eq!(bindings.get(tap), "esc".parse().ok());
+    assert_eq!(bindings.get(Trigger::Spell(0)), Some(DEFAULT_SHORTCUT));
+
+    bindings.unbind(tap);
+    assert_eq!(bindings.get(tap), None);
+}
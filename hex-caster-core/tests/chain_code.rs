@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use embassy_futures::block_on;
+use hex_caster_core::{
+    chain_code::{chain_code, edit_distance},
+    spell_compare::{Backend, SpellBook, SpellOptions, spell_compare},
+};
+
+#[test]
+fn polylines_become_chain_codes() {
+    assert_eq!(chain_code(&horizontal_line()), Ok(vec![0]));
+    assert_eq!(chain_code(&seven()), Ok(vec![0, 3]));
+    assert_eq!(chain_code(&chevron_up()), Ok(vec![7, 1]));
+    assert_eq!(chain_code(&zigzag()), Ok(vec![2, 6, 2, 6]));
+
+    // a wobbly line is still a line.
+    let wobbly = vec![(0..60).map(|i| (200 + i * 15, 400 + (i % 3) * 4)).collect()];
+    assert_eq!(chain_code(&wobbly), Ok(vec![0]));
+}
+
+#[test]
+fn edits_cost_how_far_they_turn() {
+    assert_eq!(edit_distance(&[0, 3], &[0, 3]), 0.0);
+    assert_eq!(edit_distance(&[0, 3], &[0, 2]), 0.25);
+    assert_eq!(edit_distance(&[0, 3], &[4, 3]), 1.0);
+    assert_eq!(edit_distance(&[2, 6, 2, 6], &[2, 6, 2]), 1.0);
+    assert_eq!(edit_distance(&[], &[1, 2]), 2.0);
+}
+
+#[test]
+fn angular_spells_need_one_example() {
+    let options = SpellOptions::default();
+    let mut book = SpellBook::new(Backend::ShortStraw);
+
+    for spell in [seven(), zigzag(), chevron_up(), chevron_right()] {
+        block_on(book.learn(None, options, spell, Vec::new(), 1)).unwrap();
+    }
+
+    // drawn smaller, elsewhere & with corners that are not quite the same.
+    let casts = [
+        vec![polyline(
+            &[(200.0, 200.0), (600.0, 210.0), (300.0, 600.0)],
+            12,
+        )],
+        vec![polyline(
+            &[
+                (100.0, 100.0),
+                (200.0, 450.0),
+                (300.0, 120.0),
+                (400.0, 480.0),
+                (520.0, 90.0),
+            ],
+            10,
+        )],
+        vec![polyline(
+            &[(100.0, 500.0), (300.0, 100.0), (480.0, 520.0)],
+            12,
+        )],
+        vec![polyline(
+            &[(100.0, 100.0), (500.0, 320.0), (120.0, 500.0)],
+            12,
+        )],
+    ];
+
+    for (id, cast) in casts.iter().enumerate() {
+        let recognition = block_on(spell_compare(cast, &Vec::new(), 1, &book)).unwrap();
+
+        assert_eq!(recognition.spell, id, "{recognition:?}");
+        assert!(
+            recognition.score > book.spells[id].threshold,
+            "{recognition:?}"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use embassy_time::{Duration, Instant};
+use hex_caster_core::{
+    binding::{Bindings, Shortcut, Trigger},
+    combo::{Combos, DEFAULT_COMBO_TIMEOUT, Dispatch},
+    swipe::Swipe,
+    tap::Tap,
+};
+
+const CIRCLE: Trigger = Trigger::Spell(0);
+const STAR: Trigger = Trigger::Spell(1);
+const DOWN: Trigger = Trigger::Swipe(Swipe::Down, 1);
+const TAP: Trigger = Trigger::Tap(Tap::Single);
+
+fn shortcut(keys: &str) -> Shortcut {
+    keys.parse().unwrap()
+}
+
+fn at(ms: u64) -> Instant {
+    Instant::from_millis(ms)
+}
+
+#[test]
+fn combos_fire_when_complete() {
+    let mut combos = Combos::default();
+    combos.bind(Vec::from([CIRCLE, DOWN]), shortcut("ctrl+c"));
+
+    assert_eq!(combos.push(CIRCLE, at(0)), []);
+    assert_eq!(combos.deadline(), Some(at(0) + DEFAULT_COMBO_TIMEOUT));
+    assert_eq!(
+        combos.push(DOWN, at(300)),
+        [Dispatch::Combo(shortcut("ctrl+c"))]
+    );
+    assert_eq!(combos.deadline(), None);
+}
+
+#[test]
+fn unused_triggers_pass_straight_through() {
+    let mut combos = Combos::default();
+    combos.bind(Vec::from([CIRCLE, DOWN]), shortcut("ctrl+c"));
+
+    assert_eq!(combos.push(STAR, at(0)), [Dispatch::Trigger(STAR)]);
+    assert_eq!(combos.deadline(), None);
+    assert!(combos.uses(DOWN));
+    assert!(!combos.uses(STAR));
+}
+
+#[test]
+fn broken_combos_are_dispatched_one_by_one() {
+    let mut combos = Combos::default();
+    combos.bind(Vec::from([CIRCLE, DOWN]), shortcut("ctrl+c"));
+    combos.bind(Vec::from([TAP, CIRCLE]), shortcut("ctrl+v"));
+
+    combos.push(CIRCLE, at(0));
+    assert_eq!(
+        combos.push(STAR, at(100)),
+        [Dispatch::Trigger(CIRCLE), Dispatch::Trigger(STAR)]
+    );
+
+    // the trigger that broke the combo can start another.
+    combos.push(CIRCLE, at(200));
+    assert_eq!(combos.push(TAP, at(300)), [Dispatch::Trigger(CIRCLE)]);
+    assert_eq!(
+        combos.push(CIRCLE, at(400)),
+        [Dispatch::Combo(shortcut("ctrl+v"))]
+    );
+}
+
+#[test]
+fn broken_combos_can_end_in_another() {
+    let mut combos = Combos::default();
+    combos.bind(Vec::from([CIRCLE, STAR, TAP]), shortcut("ctrl+c"));
+    combos.bind(Vec::from([STAR, DOWN]), shortcut("ctrl+v"));
+    combos.bind(Vec::from([TAP]), shortcut("ctrl+x"));
+
+    combos.push(CIRCLE, at(0));
+    combos.push(STAR, at(100));
+    assert_eq!(
+        combos.push(DOWN, at(200)),
+        [
+            Dispatch::Trigger(CIRCLE),
+            Dispatch::Combo(shortcut("ctrl+v"))
+        ]
+    );
+
+    // a combo of one trigger still fires after breaking a longer one.
+    combos.push(CIRCLE, at(1_000));
+    assert_eq!(
+        combos.push(TAP, at(1_100)),
+        [
+            Dispatch::Trigger(CIRCLE),
+            Dispatch::Combo(shortcut("ctrl+x"))
+        ]
+    );
+    assert_eq!(combos.deadline(), None);
+}
+
+#[test]
+fn shorter_combos_wait_for_the_timeout() {
+    let mut combos = Combos::default();
+    combos.timeout = Duration::from_millis(500);
+    combos.bind(Vec::from([CIRCLE, DOWN]), shortcut("ctrl+c"));
+    combos.bind(Vec::from([CIRCLE, DOWN, TAP]), shortcut("ctrl+v"));
+
+    combos.push(CIRCLE, at(0));
+    assert_eq!(combos.push(DOWN, at(100)), []);
+    assert_eq!(combos.poll(at(599)), []);
+    assert_eq!(combos.poll(at(600)), [Dispatch::Combo(shortcut("ctrl+c"))]);
+    assert_eq!(combos.deadline(), None);
+
+    // or fire the longer one.
+    combos.push(CIRCLE, at(1_000));
+    combos.push(DOWN, at(1_100));
+    assert_eq!(
+        combos.push(TAP, at(1_200)),
+        [Dispatch::Combo(shortcut("ctrl+v"))]
+    );
+}
+
+#[test]
+fn timed_out_prefixes_are_dispatched_alone() {
+    let mut combos = Combos::default();
+    combos.bind(Vec::from([CIRCLE, DOWN, TAP]), shortcut("ctrl+v"));
+
+    combos.push(CIRCLE, at(0));
+    combos.push(DOWN, at(100));
+    assert_eq!(
+        combos.poll(at(100) + DEFAULT_COMBO_TIMEOUT),
+        [Dispatch::Trigger(CIRCLE), Dispatch::Trigger(DOWN)]
+    );
+}
+
+#[test]
+fn triggers_after_the_timeout_start_over_without_a_poll() {
+    let mut combos = Combos::default();
+    combos.bind(Vec::from([CIRCLE, DOWN]), shortcut("ctrl+c"));
+
+    combos.push(CIRCLE, at(0));
+    assert_eq!(
+        combos.push(DOWN, at(0) + DEFAULT_COMBO_TIMEOUT),
+        [Dispatch::Trigger(CIRCLE), Dispatch::Trigger(DOWN)]
+    );
+    assert_eq!(combos.deadline(), None);
+
+    // a late trigger can still start the combo again.
+    combos.push(CIRCLE, at(1_000));
+    assert_eq!(
+        combos.push(CIRCLE, at(1_000) + DEFAULT_COMBO_TIMEOUT),
+        [Dispatch::Trigger(CIRCLE)]
+    );
+    assert_eq!(
+        combos.push(DOWN, at(1_100) + DEFAULT_COMBO_TIMEOUT),
+        [Dispatch::Combo(shortcut("ctrl+c"))]
+    );
+}
+
+#[test]
+fn rebinding_dispatches_the_pending_triggers() {
+    let mut combos = Combos::default();
+    combos.bind(Vec::from([CIRCLE, DOWN]), shortcut("ctrl+c"));
+
+    combos.push(CIRCLE, at(0));
+    assert_eq!(
+        combos.bind(Vec::from([STAR, TAP]), shortcut("ctrl+v")),
+        [Dispatch::Trigger(CIRCLE)]
+    );
+    assert_eq!(combos.deadline(), None);
+
+    combos.push(STAR, at(100));
+    assert_eq!(combos.unbind(&[CIRCLE, DOWN]), [Dispatch::Trigger(STAR)]);
+    assert_eq!(combos.unbind(&[STAR, TAP]), []);
+}
+
+#[test]
+fn held_spells_complete_combos_unless_the_hold_is_bound() {
+    let mut combos = Combos::default();
+    let mut bindings = Bindings::default();
+    combos.bind(Vec::from([CIRCLE, STAR]), shortcut("ctrl+c"));
+
+    // resting at the end of the star by accident still finishes the combo.
+    combos.push(combos.spell_trigger(0, false, &bindings), at(0));
+    assert_eq!(
+        combos.push(combos.spell_trigger(1, true, &bindings), at(100)),
+        [Dispatch::Combo(shortcut("ctrl+c"))]
+    );
+    assert!(!combos.holds());
+
+    // unless holding the star does something else.
+    bindings.bind(Trigger::Held(1), shortcut("ctrl+v"));
+    assert!(bindings.holds());
+
+    combos.push(combos.spell_trigger(0, false, &bindings), at(1_000));
+    assert_eq!(
+        combos.push(combos.spell_trigger(1, true, &bindings), at(1_100)),
+        [
+            Dispatch::Trigger(CIRCLE),
+            Dispatch::Trigger(Trigger::Held(1))
+        ]
+    );
+
+    // or is part of a combo itself.
+    bindings.unbind(Trigger::Held(1));
+    combos.bind(Vec::from([Trigger::Held(1), TAP]), shortcut("ctrl+x"));
+    assert!(!bindings.holds());
+    assert!(combos.holds());
+    assert_eq!(combos.spell_trigger(1, true, &bindings), Trigger::Held(1));
+    assert_eq!(combos.spell_trigger(0, true, &bindings), CIRCLE);
+}
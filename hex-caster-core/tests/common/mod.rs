@@ -0,0 +1,171 @@
+//! synthetic spells drawn in touch-pad coordinates, inside `DEFAULT_PAD_SIZE`.
+
+#![allow(dead_code)]
+
+use core::f32::consts::TAU;
+
+use embassy_time::Duration;
+use hex_caster_core::{Point, Spell, Stroke, Timing};
+
+pub const CENTER: (f32, f32) = (650.0, 400.0);
+
+fn point(x: f32, y: f32) -> Point {
+    (x.round() as u16, y.round() as u16)
+}
+
+/// `n` points evenly spaced between `a` & `b`.
+pub fn line(a: (f32, f32), b: (f32, f32), n: usize) -> Stroke {
+    (0..n)
+        .map(|i| {
+            let t = i as f32 / (n - 1) as f32;
+            point(a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1))
+        })
+        .collect()
+}
+
+/// a stroke through every corner in order.
+pub fn polyline(corners: &[(f32, f32)], n_per_segment: usize) -> Stroke {
+    let mut stroke = Stroke::new();
+
+    for pair in corners.windows(2) {
+        let mut segment = line(pair[0], pair[1], n_per_segment);
+
+        if !stroke.is_empty() {
+            segment.remove(0);
+        }
+
+        stroke.extend(segment);
+    }
+
+    stroke
+}
+
+pub fn circle(radius: f32, start: f32) -> Spell {
+    let stroke = (0..=64)
+        .map(|i| {
+            let a = start + TAU * i as f32 / 64.0;
+            point(CENTER.0 + radius * a.cos(), CENTER.1 + radius * a.sin())
+        })
+        .collect();
+
+    vec![stroke]
+}
+
+/// a circle whose radius wobbles by `wobble` of itself, in & out 5 times on the way round.
+pub fn wobbly_circle(radius: f32, wobble: f32) -> Spell {
+    let stroke = (0..=64)
+        .map(|i| {
+            let a = TAU * i as f32 / 64.0;
+            let r = radius * (1.0 + wobble * (5.0 * a).sin());
+            point(CENTER.0 + r * a.cos(), CENTER.1 + r * a.sin())
+        })
+        .collect();
+
+    vec![stroke]
+}
+
+pub fn horizontal_line() -> Spell {
+    vec![line((260.0, 400.0), (1_040.0, 400.0), 40)]
+}
+
+pub fn zigzag() -> Spell {
+    vec![polyline(
+        &[
+            (300.0, 50.0),
+            (475.0, 750.0),
+            (650.0, 50.0),
+            (825.0, 750.0),
+            (1_000.0, 50.0),
+        ],
+        15,
+    )]
+}
+
+/// a "^" pointing up.
+pub fn chevron_up() -> Spell {
+    vec![polyline(
+        &[(300.0, 750.0), (650.0, 50.0), (1_000.0, 750.0)],
+        20,
+    )]
+}
+
+/// a ">" pointing right.
+pub fn chevron_right() -> Spell {
+    vec![polyline(
+        &[(300.0, 50.0), (1_000.0, 400.0), (300.0, 750.0)],
+        20,
+    )]
+}
+
+/// an "X", drawn as two strokes.
+pub fn cross() -> Spell {
+    vec![
+        line((300.0, 50.0), (1_000.0, 750.0), 30),
+        line((1_000.0, 50.0), (300.0, 750.0), 30),
+    ]
+}
+
+/// a "=", drawn as two strokes.
+pub fn equals() -> Spell {
+    vec![
+        line((300.0, 260.0), (1_000.0, 260.0), 30),
+        line((300.0, 540.0), (1_000.0, 540.0), 30),
+    ]
+}
+
+/// a "7", which is nothing like itself mirrored either way.
+pub fn seven() -> Spell {
+    vec![polyline(
+        &[(300.0, 50.0), (1_000.0, 50.0), (440.0, 750.0)],
+        20,
+    )]
+}
+
+/// `spell` flipped left to right, as drawn with the other hand.
+pub fn flipped(spell: &Spell) -> Spell {
+    spell
+        .iter()
+        .map(|stroke| stroke.iter().map(|&(x, y)| (1_300 - x, y)).collect())
+        .collect()
+}
+
+/// `spell` flipped top to bottom.
+pub fn upside_down(spell: &Spell) -> Spell {
+    spell
+        .iter()
+        .map(|stroke| stroke.iter().map(|&(x, y)| (x, 800 - y)).collect())
+        .collect()
+}
+
+/// a small check mark with its top left corner at `origin`.
+pub fn check_mark(origin: (f32, f32)) -> Spell {
+    vec![polyline(
+        &[
+            (origin.0, origin.1 + 40.0),
+            (origin.0 + 40.0, origin.1 + 80.0),
+            (origin.0 + 120.0, origin.1),
+        ],
+        15,
+    )]
+}
+
+/// the spell drawn a point every `ms` milliseconds, with `gap` between strokes.
+pub fn steady(spell: &Spell, ms: u64, gap: u64) -> Timing {
+    let mut t = 0;
+
+    spell
+        .iter()
+        .map(|stroke| {
+            let times = stroke
+                .iter()
+                .map(|_| {
+                    t += ms;
+                    Duration::from_millis(t - ms)
+                })
+                .collect();
+            t += gap;
+
+            times
+        })
+        .collect()
+}
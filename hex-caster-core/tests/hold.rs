@@ -0,0 +1,55 @@
+//! every test binary has its own mock time driver, so the holds are kept apart from the rest of
+//! `SpellBuilder`'s timing in `spell_builder`.
+
+use embassy_time::{Duration, MockDriver};
+use hex_caster_core::{
+    spell_caster::{HOLD_TIME, STROKE_GAP, SpellBuilder},
+    tap::{LONG_PRESS, Tap},
+};
+
+#[test]
+fn strokes_resting_at_the_end_are_held() {
+    let driver = MockDriver::get();
+    let mut builder = SpellBuilder::default();
+    driver.advance(Duration::from_secs(1));
+
+    // a stroke that rests at its end before lifting, jittering, is held. the hold is told once,
+    // while the finger is still down.
+    builder.step((100, 100));
+    builder.step((300, 100));
+    driver.advance(HOLD_TIME / 2);
+    builder.step((305, 102));
+    assert!(!builder.hold_reached());
+    driver.advance(HOLD_TIME / 2);
+    assert!(builder.hold_reached());
+    assert!(!builder.hold_reached());
+    builder.step((0, 0));
+    assert!(builder.held());
+
+    // moving on after resting is not a hold, if the finger lifts straight away.
+    builder.reset();
+    builder.step((100, 100));
+    driver.advance(HOLD_TIME);
+    builder.step((300, 100));
+    builder.step((0, 0));
+    assert!(!builder.held());
+
+    // nor is a long press, which never moved.
+    builder.reset();
+    builder.step((400, 300));
+    driver.advance(LONG_PRESS);
+    builder.step((0, 0));
+    driver.advance(STROKE_GAP);
+    assert_eq!(builder.tap(), Some(Tap::LongPress));
+    assert!(!builder.held());
+
+    // & nothing is, once holds are turned off.
+    builder.reset();
+    builder.hold_time = None;
+    builder.step((100, 100));
+    builder.step((300, 100));
+    driver.advance(HOLD_TIME);
+    assert!(!builder.hold_reached());
+    builder.step((0, 0));
+    assert!(!builder.held());
+}
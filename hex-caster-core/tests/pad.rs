@@ -0,0 +1,78 @@
+mod common;
+
+use common::*;
+use hex_caster_core::pad::{
+    DEFAULT_PAD_SIZE, PadRegion, SizeClass, bounding_box, logical_maximums,
+};
+
+#[test]
+fn regions_follow_the_bounding_box() {
+    let (width, height) = (DEFAULT_PAD_SIZE.0 as f32, DEFAULT_PAD_SIZE.1 as f32);
+
+    assert_eq!(
+        PadRegion::of(&check_mark((10.0, 10.0))),
+        Some(PadRegion::TopLeft)
+    );
+    assert_eq!(
+        PadRegion::of(&check_mark((width / 2.0 - 60.0, height / 2.0 - 40.0))),
+        Some(PadRegion::Centre)
+    );
+    assert_eq!(
+        PadRegion::of(&check_mark((width - 130.0, height - 90.0))),
+        Some(PadRegion::BottomRight)
+    );
+    assert_eq!(
+        PadRegion::of(&check_mark((width - 130.0, height / 2.0 - 40.0))),
+        Some(PadRegion::Right)
+    );
+    // past the edge of the pad still counts as the edge.
+    assert_eq!(
+        PadRegion::of(&vec![line((60_000.0, 10.0), (61_000.0, 10.0), 10)]),
+        Some(PadRegion::TopRight)
+    );
+    assert_eq!(PadRegion::of(&vec![]), None);
+}
+
+#[test]
+fn bounding_box_spans_every_stroke() {
+    assert_eq!(bounding_box(&cross()), Some(((300, 50), (1_000, 750))));
+    assert_eq!(bounding_box(&vec![vec![]]), None);
+}
+
+#[test]
+fn sizes_follow_the_longest_side() {
+    assert_eq!(SizeClass::of(&circle(50.0, 0.0)), Some(SizeClass::Small));
+    assert_eq!(SizeClass::of(&circle(150.0, 0.0)), Some(SizeClass::Medium));
+    assert_eq!(SizeClass::of(&circle(300.0, 0.0)), Some(SizeClass::Large));
+    // a long thin line is as big as its length.
+    assert_eq!(SizeClass::of(&horizontal_line()), Some(SizeClass::Large));
+    assert_eq!(SizeClass::of(&vec![]), None);
+}
+
+#[test]
+fn pad_size_comes_from_the_report_descriptor() {
+    #[rustfmt::skip]
+    let descriptor = [
+        0x05, 0x0d,             // usage page (digitizer)
+        0x09, 0x30,             //   usage (tip pressure), not x
+        0x26, 0xff, 0x00,       //   logical maximum (255)
+        0x81, 0x02,             //   input
+        0x05, 0x01,             // usage page (generic desktop)
+        0xa4,                   //   push
+        0x09, 0x30,             //   usage (x)
+        0x26, 0x14, 0x05,       //   logical maximum (1300)
+        0x81, 0x02,             //   input
+        0x09, 0x31,             //   usage (y)
+        0x26, 0x20, 0x03,       //   logical maximum (800)
+        0x81, 0x02,             //   input
+        0xb4,                   //   pop
+        0x09, 0x30,             //   usage (x) of a second contact, smaller
+        0x26, 0x00, 0x01,       //   logical maximum (256)
+        0x81, 0x02,             //   input
+    ];
+
+    assert_eq!(logical_maximums(&descriptor), Some(DEFAULT_PAD_SIZE));
+    // no y, & cut off in the middle of an item.
+    assert_eq!(logical_maximums(&descriptor[..19]), None);
+    assert_eq!(logical_maximums(&descriptor[..16]), None);
+}
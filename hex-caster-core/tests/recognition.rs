@@ -0,0 +1,674 @@
+mod common;
+
+use common::*;
+use embassy_futures::block_on;
+use hex_caster_core::{
+    Spell,
+    spell_compare::{
+        Backend, DEFAULT_THRESHOLD, Recognition, RecognizeError, SpellBook, SpellOptions,
+        spell_compare, spell_compare_prefix, spell_compare_top_k,
+    },
+};
+
+const BACKENDS: [Backend; 4] = [
+    Backend::DollarOne,
+    Backend::Protractor,
+    Backend::DollarP,
+    Backend::ShortStraw,
+];
+
+fn book(backend: Backend, spells: &[(Spell, SpellOptions)]) -> SpellBook {
+    let mut book = SpellBook::new(backend);
+
+    for (spell, options) in spells.iter() {
+        block_on(book.learn(None, *options, spell.clone(), Vec::new(), 1)).unwrap();
+    }
+
+    book
+}
+
+fn recognize(book: &SpellBook, spell: &Spell) -> Recognition {
+    block_on(spell_compare(spell, &Vec::new(), 1, book)).unwrap()
+}
+
+#[test]
+fn identical_spell_matches_on_every_backend() {
+    let options = SpellOptions::default();
+
+    for backend in BACKENDS {
+        let book = book(
+            backend,
+            &[(circle(300.0, 0.0), options), (zigzag(), options)],
+        );
+        let recognition = recognize(&book, &circle(300.0, 0.0));
+
+        assert_eq!(recognition.spell, 0, "{backend:?}");
+        assert!(
+            recognition.score > 0.95,
+            "{backend:?}: {}",
+            recognition.score
+        );
+    }
+}
+
+#[test]
+fn shapes_are_told_apart() {
+    let options = SpellOptions::default();
+
+    for backend in BACKENDS {
+        let book = book(
+            backend,
+            &[
+                (circle(300.0, 0.0), options),
+                (horizontal_line(), options),
+                (zigzag(), options),
+            ],
+        );
+
+        // a bigger circle, so the templates are not compared to themselves.
+        assert_eq!(
+            recognize(&book, &circle(450.0, 0.0)).spell,
+            0,
+            "{backend:?}"
+        );
+        assert_eq!(
+            recognize(&book, &vec![line((200.0, 300.0), (900.0, 300.0), 25)]).spell,
+            1,
+            "{backend:?}"
+        );
+        assert_eq!(recognize(&book, &zigzag()).spell, 2, "{backend:?}");
+    }
+}
+
+#[test]
+fn rotation_sensitive_spells_stay_distinct() {
+    let options = SpellOptions {
+        rotation_sensitive: true,
+        ..SpellOptions::default()
+    };
+
+    for backend in [Backend::DollarOne, Backend::Protractor] {
+        let book = book(
+            backend,
+            &[(chevron_up(), options), (chevron_right(), options)],
+        );
+
+        assert_eq!(recognize(&book, &chevron_up()).spell, 0, "{backend:?}");
+        assert_eq!(recognize(&book, &chevron_right()).spell, 1, "{backend:?}");
+    }
+}
+
+#[test]
+fn rotation_insensitive_spells_match_turned() {
+    let options = SpellOptions {
+        rotation_sensitive: false,
+        ..SpellOptions::default()
+    };
+
+    for backend in [Backend::DollarOne, Backend::Protractor] {
+        let book = book(backend, &[(chevron_up(), options), (zigzag(), options)]);
+        let recognition = recognize(&book, &chevron_right());
+
+        assert_eq!(recognition.spell, 0, "{backend:?}");
+        assert!(
+            recognition.score > 0.9,
+            "{backend:?}: {}",
+            recognition.score
+        );
+    }
+}
+
+#[test]
+fn direction_sensitive_circles_stay_distinct() {
+    let options = SpellOptions::default();
+    let mut counter_clockwise = circle(300.0, 0.0);
+    counter_clockwise[0].reverse();
+
+    for backend in [Backend::DollarOne, Backend::Protractor] {
+        let book = book(
+            backend,
+            &[
+                (circle(300.0, 0.0), options),
+                (counter_clockwise.clone(), options),
+            ],
+        );
+
+        assert_eq!(
+            recognize(&book, &circle(400.0, 0.0)).spell,
+            0,
+            "{backend:?}"
+        );
+        assert_eq!(recognize(&book, &counter_clockwise).spell, 1, "{backend:?}");
+    }
+}
+
+#[test]
+fn direction_insensitive_spells_match_reversed() {
+    let options = SpellOptions {
+        direction_sensitive: false,
+        ..SpellOptions::default()
+    };
+    let mut backwards = zigzag();
+    backwards[0].reverse();
+
+    for backend in BACKENDS {
+        let book = book(
+            backend,
+            &[(zigzag(), options), (circle(300.0, 0.0), options)],
+        );
+        let recognition = recognize(&book, &backwards);
+
+        assert_eq!(recognition.spell, 0, "{backend:?}");
+        assert!(
+            recognition.score > 0.95,
+            "{backend:?}: {}",
+            recognition.score
+        );
+    }
+}
+
+#[test]
+fn mirror_invariant_spells_match_mirrored() {
+    let options = SpellOptions {
+        mirror_invariant: true,
+        ..SpellOptions::default()
+    };
+
+    for backend in BACKENDS {
+        let book = book(backend, &[(seven(), options), (zigzag(), options)]);
+
+        for cast in [flipped(&seven()), upside_down(&seven())] {
+            let recognition = recognize(&book, &cast);
+
+            assert_eq!(recognition.spell, 0, "{backend:?}");
+            assert!(
+                recognition.score > 0.95,
+                "{backend:?}: {}",
+                recognition.score
+            );
+        }
+    }
+}
+
+#[test]
+fn mirroring_the_book_keeps_spell_options() {
+    let options = SpellOptions::default();
+
+    for backend in BACKENDS {
+        let mut book = book(backend, &[(seven(), options), (zigzag(), options)]);
+        let before = recognize(&book, &flipped(&seven())).score;
+
+        block_on(book.set_mirror_invariant(true));
+        let recognition = recognize(&book, &flipped(&seven()));
+
+        assert!(book.mirror_invariant(), "{backend:?}");
+        assert_eq!(recognition.spell, 0, "{backend:?}");
+        assert!(recognition.score > before, "{backend:?}: {before}");
+        assert!(
+            recognition.score > 0.95,
+            "{backend:?}: {}",
+            recognition.score
+        );
+
+        block_on(book.set_mirror_invariant(false));
+
+        assert!(!book.spells[0].options.mirror_invariant, "{backend:?}");
+        assert_eq!(
+            recognize(&book, &flipped(&seven())).score,
+            before,
+            "{backend:?}"
+        );
+    }
+}
+
+#[test]
+fn position_aware_spells_are_told_apart_by_region() {
+    let options = SpellOptions {
+        position_aware: true,
+        ..SpellOptions::default()
+    };
+    let (top_left, bottom_right) = ((20.0, 20.0), (1_150.0, 700.0));
+
+    for backend in BACKENDS {
+        let book = book(
+            backend,
+            &[
+                (check_mark(top_left), options),
+                (check_mark(bottom_right), options),
+            ],
+        );
+
+        assert_eq!(
+            recognize(&book, &check_mark((40.0, 30.0))).spell,
+            0,
+            "{backend:?}"
+        );
+        assert_eq!(
+            recognize(&book, &check_mark((1_130.0, 690.0))).spell,
+            1,
+            "{backend:?}"
+        );
+        assert_eq!(
+            recognize(&book, &check_mark((600.0, 360.0))).score,
+            0.0,
+            "{backend:?}"
+        );
+    }
+}
+
+#[test]
+fn size_aware_spells_are_told_apart_by_size() {
+    let options = SpellOptions {
+        size_aware: true,
+        ..SpellOptions::default()
+    };
+
+    for backend in BACKENDS {
+        let book = book(
+            backend,
+            &[(circle(50.0, 0.0), options), (circle(300.0, 0.0), options)],
+        );
+
+        assert_eq!(recognize(&book, &circle(60.0, 0.2)).spell, 0, "{backend:?}");
+        assert_eq!(
+            recognize(&book, &circle(280.0, 0.2)).spell,
+            1,
+            "{backend:?}"
+        );
+        assert_eq!(
+            recognize(&book, &circle(150.0, 0.2)).score,
+            0.0,
+            "{backend:?}"
+        );
+    }
+}
+
+#[test]
+fn time_warped_spells_line_up_uneven_strokes() {
+    // the first leg drawn much shorter, so point i of it is not point i of the template.
+    let uneven = vec![polyline(
+        &[(300.0, 750.0), (405.0, 50.0), (1_000.0, 750.0)],
+        20,
+    )];
+    let score = |options: SpellOptions, spell: &Spell| {
+        let spells = [(chevron_up(), options), (seven(), options)];
+
+        BACKENDS.map(|backend| {
+            let recognition = recognize(&book(backend, &spells), spell);
+            assert_eq!(recognition.spell, 0, "{backend:?}");
+            recognition.score
+        })
+    };
+
+    let warped = SpellOptions {
+        time_warped: true,
+        ..SpellOptions::default()
+    };
+    let plain = score(SpellOptions::default(), &uneven)[0];
+
+    // the warped score is the same whatever the backend.
+    for s in score(warped, &uneven) {
+        assert!(s > plain + 0.05, "{s} vs {plain}");
+    }
+
+    let reversed = vec![uneven[0].iter().rev().copied().collect()];
+    for s in score(
+        SpellOptions {
+            direction_sensitive: false,
+            ..warped
+        },
+        &reversed,
+    ) {
+        assert!(s > plain + 0.05, "{s} vs {plain}");
+    }
+}
+
+#[test]
+fn finger_count_is_part_of_the_spell() {
+    let options = SpellOptions::default();
+
+    for backend in BACKENDS {
+        let mut book = SpellBook::new(backend);
+        block_on(book.learn(None, options, circle(300.0, 0.0), Vec::new(), 1)).unwrap();
+        block_on(book.learn(None, options, circle(300.0, 0.0), Vec::new(), 2)).unwrap();
+        block_on(book.learn(None, options, zigzag(), Vec::new(), 2)).unwrap();
+
+        assert_eq!(
+            block_on(book.learn(Some(0), options, circle(400.0, 0.0), Vec::new(), 2)),
+            Err(RecognizeError::WrongFingers),
+            "{backend:?}"
+        );
+
+        let one = block_on(spell_compare(&circle(350.0, 0.0), &Vec::new(), 1, &book)).unwrap();
+        let two = block_on(spell_compare(&circle(350.0, 0.0), &Vec::new(), 2, &book)).unwrap();
+
+        assert_eq!((one.spell, one.runner_up), (0, None), "{backend:?}");
+        assert_eq!(
+            (two.spell, two.runner_up.map(|(id, _)| id)),
+            (1, Some(2)),
+            "{backend:?}"
+        );
+        assert_eq!(
+            block_on(spell_compare(&circle(350.0, 0.0), &Vec::new(), 3, &book)).err(),
+            Some(RecognizeError::EmptyCorpus),
+            "{backend:?}"
+        );
+    }
+}
+
+#[test]
+fn examples_of_unknown_spells_are_rejected() {
+    let options = SpellOptions::default();
+
+    for backend in BACKENDS.into_iter().chain([Backend::Rubine]) {
+        let mut book = SpellBook::new(backend);
+        block_on(book.learn(None, options, circle(300.0, 0.0), Vec::new(), 1)).unwrap();
+
+        for id in [1, 5] {
+            assert_eq!(
+                block_on(book.learn(Some(id), options, zigzag(), Vec::new(), 1)),
+                Err(RecognizeError::UnknownSpell),
+                "{backend:?}"
+            );
+        }
+
+        // & the recognizer was not left with a template of a spell that does not exist.
+        let recognition = recognize(&book, &zigzag());
+        assert_eq!(book.spells.len(), 1, "{backend:?}");
+        assert_eq!(
+            (recognition.spell, recognition.runner_up),
+            (0, None),
+            "{backend:?}"
+        );
+    }
+}
+
+/// the first `fraction` of the points of a single stroke spell.
+fn partial(spell: &Spell, fraction: f32) -> Spell {
+    let stroke = &spell[0];
+
+    vec![stroke[..(stroke.len() as f32 * fraction) as usize].to_vec()]
+}
+
+#[test]
+fn unfinished_spells_are_recognized_early() {
+    let options = SpellOptions::default();
+
+    // $P scores partial clouds too harshly to ever be decisive, so it only casts on lift.
+    for backend in [Backend::DollarOne, Backend::Protractor] {
+        let book = book(
+            backend,
+            &[
+                (circle(300.0, 0.0), options),
+                (zigzag(), options),
+                (seven(), options),
+            ],
+        );
+
+        for (id, spell) in [(0, circle(300.0, 0.0)), (1, zigzag()), (2, seven())] {
+            // previewed as it is drawn, the first decisive recognition is what gets cast.
+            let early = (1..10)
+                .map(|tenths| {
+                    block_on(spell_compare_prefix(
+                        &partial(&spell, tenths as f32 / 10.0),
+                        1,
+                        &book,
+                    ))
+                })
+                .filter_map(Result::ok)
+                .find(|recognition| {
+                    recognition.is_decisive(book.spells[recognition.spell].threshold)
+                });
+
+            assert_eq!(
+                early.map(|recognition| recognition.spell),
+                Some(id),
+                "{backend:?}"
+            );
+        }
+
+        // a finished spell is its own prefix.
+        let recognition = block_on(spell_compare_prefix(&zigzag(), 1, &book)).unwrap();
+        assert_eq!(recognition.spell, 1, "{backend:?}");
+    }
+}
+
+#[test]
+fn multistroke_spells_are_not_recognized_early() {
+    let book = book(Backend::DollarOne, &[(cross(), SpellOptions::default())]);
+
+    assert_eq!(
+        block_on(spell_compare_prefix(&partial(&cross(), 0.5), 1, &book)).err(),
+        Some(RecognizeError::EmptyCorpus)
+    );
+}
+
+#[test]
+fn decisive_needs_a_lead() {
+    let sure = Recognition::from_candidates(&[(0, 0.95), (1, 0.6)]).unwrap();
+    let close = Recognition::from_candidates(&[(0, 0.95), (1, 0.9)]).unwrap();
+    let weak = Recognition::from_candidates(&[(0, 0.7)]).unwrap();
+
+    assert!(sure.is_decisive(0.6));
+    assert!(!sure.is_decisive(0.96));
+    assert!(!close.is_decisive(0.6));
+    assert!(!weak.is_decisive(0.6));
+}
+
+#[test]
+fn one_dimensional_spells_are_learned() {
+    for backend in BACKENDS {
+        let mut book = SpellBook::new(backend);
+
+        assert_eq!(
+            block_on(book.learn(
+                None,
+                SpellOptions::default(),
+                horizontal_line(),
+                Vec::new(),
+                1
+            )),
+            Ok(0),
+            "{backend:?}"
+        );
+    }
+}
+
+#[test]
+fn bad_spells_are_rejected() {
+    for backend in BACKENDS {
+        let mut book = SpellBook::new(backend);
+        let options = SpellOptions::default();
+
+        assert_eq!(
+            block_on(spell_compare(&circle(300.0, 0.0), &Vec::new(), 1, &book)).err(),
+            Some(RecognizeError::EmptyCorpus),
+            "{backend:?}"
+        );
+        assert_eq!(
+            block_on(book.learn(None, options, vec![vec![(1, 1), (2, 2)]], Vec::new(), 1)),
+            Err(RecognizeError::TooShort),
+            "{backend:?}"
+        );
+        assert_eq!(
+            block_on(book.learn(None, options, vec![vec![(7, 7); 20]], Vec::new(), 1)),
+            Err(RecognizeError::Degenerate),
+            "{backend:?}"
+        );
+        // neither failure should leave an empty spell behind.
+        assert!(book.spells.is_empty(), "{backend:?}");
+    }
+}
+
+#[test]
+fn multistroke_spells_ignore_stroke_order() {
+    let options = SpellOptions::default();
+
+    for backend in BACKENDS {
+        let book = book(backend, &[(cross(), options), (equals(), options)]);
+        let mut reversed = cross();
+        reversed.reverse();
+
+        assert_eq!(recognize(&book, &reversed).spell, 0, "{backend:?}");
+        assert_eq!(recognize(&book, &equals()).spell, 1, "{backend:?}");
+    }
+}
+
+#[test]
+fn thresholds_come_from_the_examples() {
+    let options = SpellOptions::default();
+
+    for backend in BACKENDS {
+        let mut book = SpellBook::new(backend);
+        block_on(book.learn(None, options, circle(300.0, 0.0), Vec::new(), 1)).unwrap();
+        assert_eq!(book.spells[0].threshold, DEFAULT_THRESHOLD, "{backend:?}");
+
+        block_on(book.learn(Some(0), options, circle(400.0, 0.3), Vec::new(), 1)).unwrap();
+        block_on(book.learn(Some(0), options, circle(350.0, 0.6), Vec::new(), 1)).unwrap();
+        let threshold = book.spells[0].threshold;
+
+        assert_eq!(book.spells[0].examples.len(), 3, "{backend:?}");
+        assert!(
+            (0.5..=0.95).contains(&threshold),
+            "{backend:?}: {threshold}"
+        );
+    }
+}
+
+#[test]
+fn thresholds_set_by_hand_are_kept() {
+    let options = SpellOptions::default();
+    let mut book = book(Backend::Protractor, &[(circle(300.0, 0.0), options)]);
+
+    assert_eq!(book.set_threshold(0, 0.42), Some(DEFAULT_THRESHOLD));
+    assert_eq!(book.set_threshold(1, 0.42), None);
+
+    // not by another example, a new backend or mirroring the whole book.
+    block_on(book.learn(Some(0), options, circle(400.0, 0.3), Vec::new(), 1)).unwrap();
+    block_on(book.set_backend(Backend::DollarP));
+    block_on(book.set_mirror_invariant(true));
+
+    assert_eq!(book.spells[0].threshold, 0.42);
+}
+
+#[test]
+fn candidates_are_sorted_best_first() {
+    let options = SpellOptions::default();
+
+    for backend in BACKENDS {
+        let book = book(
+            backend,
+            &[
+                (zigzag(), options),
+                (circle(300.0, 0.0), options),
+                (horizontal_line(), options),
+            ],
+        );
+        let candidates = block_on(spell_compare_top_k(
+            &circle(300.0, 0.0),
+            &Vec::new(),
+            1,
+            &book,
+            2,
+        ))
+        .unwrap();
+
+        assert_eq!(candidates.len(), 2, "{backend:?}");
+        assert_eq!(candidates[0].0, 1, "{backend:?}");
+        assert!(candidates[0].1 >= candidates[1].1, "{backend:?}");
+    }
+}
+
+#[test]
+fn close_runner_up_is_ambiguous() {
+    let recognition = Recognition::from_candidates(&[(0, 0.9), (1, 0.88)]).unwrap();
+
+    assert!(recognition.is_ambiguous(0.05));
+    assert!(!recognition.is_ambiguous(0.01));
+    assert!(
+        !Recognition::from_candidates(&[(0, 0.9)])
+            .unwrap()
+            .is_ambiguous(0.05)
+    );
+    assert!(Recognition::from_candidates(&[]).is_none());
+}
+
+#[test]
+fn switching_backend_keeps_spells() {
+    let options = SpellOptions::default();
+    let mut book = book(
+        Backend::DollarOne,
+        &[(circle(300.0, 0.0), options), (zigzag(), options)],
+    );
+
+    block_on(book.set_backend(Backend::DollarP));
+
+    assert_eq!(book.backend(), Backend::DollarP);
+    assert_eq!(recognize(&book, &zigzag()).spell, 1);
+}
+
+#[test]
+fn forgotten_spells_keep_their_ids() {
+    let options = SpellOptions::default();
+
+    for backend in BACKENDS {
+        let mut book = book(
+            backend,
+            &[(circle(300.0, 0.0), options), (zigzag(), options)],
+        );
+
+        assert!(book.forget(0), "{backend:?}");
+        assert!(!book.forget(5), "{backend:?}");
+        assert_eq!(
+            recognize(&book, &circle(300.0, 0.0)).spell,
+            1,
+            "{backend:?}"
+        );
+        assert_eq!(
+            block_on(book.learn(None, options, horizontal_line(), Vec::new(), 1)),
+            Ok(2),
+            "{backend:?}"
+        );
+    }
+}
+
+#[test]
+fn backends_parse() {
+    assert_eq!("$1".parse(), Ok(Backend::DollarOne));
+    assert_eq!("protractor".parse(), Ok(Backend::Protractor));
+    assert_eq!("$p".parse(), Ok(Backend::DollarP));
+    assert_eq!("shortstraw".parse(), Ok(Backend::ShortStraw));
+    assert_eq!("rubine".parse(), Ok(Backend::Rubine));
+    assert!("$2".parse::<Backend>().is_err());
+}
+
+#[test]
+fn sloppy_spells_clear_the_default_threshold() {
+    let options = SpellOptions::default();
+    // an "X" with its strokes a little off.
+    let sloppy_cross = vec![
+        line((620.0, 580.0), (1_380.0, 1_420.0), 30),
+        line((1_420.0, 640.0), (600.0, 1_380.0), 30),
+    ];
+
+    for backend in [Backend::DollarOne, Backend::Protractor, Backend::DollarP] {
+        let book = book(
+            backend,
+            &[(circle(300.0, 0.0), options), (cross(), options)],
+        );
+
+        for (spell, id) in [
+            (wobbly_circle(300.0, 0.1), 0),
+            (wobbly_circle(300.0, 0.2), 0),
+            (sloppy_cross.clone(), 1),
+        ] {
+            let recognition = recognize(&book, &spell);
+
+            assert_eq!(recognition.spell, id, "{backend:?}");
+            assert!(
+                recognition.score > DEFAULT_THRESHOLD,
+                "{backend:?} {recognition:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use common::*;
+use embassy_futures::block_on;
+use hex_caster_core::{
+    Spell,
+    spell_compare::{
+        Backend, Recognition, RecognizeError, SpellBook, SpellOptions, spell_compare,
+        spell_compare_prefix,
+    },
+};
+
+/// learns each list of examples as a spell, in order.
+fn book(spells: &[(&[Spell], SpellOptions)]) -> SpellBook {
+    let mut book = SpellBook::new(Backend::Rubine);
+
+    for (examples, options) in spells.iter() {
+        let mut id = None;
+
+        for example in examples.iter() {
+            id = Some(block_on(book.learn(id, *options, example.clone(), Vec::new(), 1)).unwrap());
+        }
+    }
+
+    book
+}
+
+fn recognize(book: &SpellBook, spell: &Spell) -> Recognition {
+    block_on(spell_compare(spell, &Vec::new(), 1, book)).unwrap()
+}
+
+fn is_cast(book: &SpellBook, recognition: &Recognition) -> bool {
+    recognition.score > book.spells[recognition.spell].threshold
+}
+
+fn circles() -> [Spell; 3] {
+    [circle(300.0, 0.0), circle(350.0, 0.1), circle(250.0, -0.1)]
+}
+
+fn lines() -> [Spell; 3] {
+    [
+        horizontal_line(),
+        vec![line((230.0, 330.0), (860.0, 365.0), 30)],
+        vec![line((370.0, 470.0), (1_140.0, 435.0), 50)],
+    ]
+}
+
+fn zigzags() -> [Spell; 2] {
+    let smaller = zigzag()
+        .iter()
+        .map(|stroke| {
+            stroke
+                .iter()
+                .map(|&(x, y)| (x / 2 + 300, y / 2 + 300))
+                .collect()
+        })
+        .collect();
+
+    [zigzag(), smaller]
+}
+
+#[test]
+fn shapes_are_told_apart() {
+    let options = SpellOptions::default();
+    let book = book(&[
+        (&circles(), options),
+        (&lines(), options),
+        (&zigzags(), options),
+    ]);
+
+    for (spell, id) in [
+        (circle(320.0, 0.05), 0),
+        (vec![line((300.0, 400.0), (790.0, 386.0), 35)], 1),
+        (zigzag(), 2),
+    ] {
+        let recognition = recognize(&book, &spell);
+
+        assert_eq!(recognition.spell, id);
+        assert!(is_cast(&book, &recognition), "{recognition:?}");
+    }
+}
+
+#[test]
+fn outliers_are_not_cast() {
+    let options = SpellOptions::default();
+    let book = book(&[(&circles(), options), (&lines(), options)]);
+
+    assert!(!is_cast(&book, &recognize(&book, &zigzag())));
+}
+
+#[test]
+fn multistroke_spells_need_as_many_strokes() {
+    let options = SpellOptions::default();
+    let book = book(&[(&[cross()], options), (&[equals()], options)]);
+
+    assert_eq!(recognize(&book, &cross()).spell, 0);
+    assert_eq!(recognize(&book, &equals()).spell, 1);
+    assert_eq!(recognize(&book, &horizontal_line()).score, 0.0);
+}
+
+#[test]
+fn direction_insensitive_spells_match_reversed() {
+    let reversed = |spell: &Spell| -> Spell {
+        spell
+            .iter()
+            .rev()
+            .map(|stroke| stroke.iter().rev().copied().collect())
+            .collect()
+    };
+
+    for direction_sensitive in [true, false] {
+        let options = SpellOptions {
+            direction_sensitive,
+            ..SpellOptions::default()
+        };
+        let book = book(&[(&[seven()], options), (&circles(), options)]);
+        let recognition = recognize(&book, &reversed(&seven()));
+
+        assert_eq!(
+            recognition.spell == 0 && is_cast(&book, &recognition),
+            !direction_sensitive,
+            "{recognition:?}"
+        );
+    }
+}
+
+#[test]
+fn bad_spells_are_rejected() {
+    let mut book = SpellBook::new(Backend::Rubine);
+    let options = SpellOptions::default();
+
+    assert_eq!(
+        block_on(book.learn(None, options, vec![vec![(1, 1), (2, 2)]], Vec::new(), 1)),
+        Err(RecognizeError::TooShort)
+    );
+    assert_eq!(
+        block_on(book.learn(None, options, vec![vec![(7, 7); 20]], Vec::new(), 1)),
+        Err(RecognizeError::Degenerate)
+    );
+    assert!(book.spells.is_empty());
+}
+
+#[test]
+fn unfinished_spells_are_not_previewed() {
+    let book = book(&[(&circles(), SpellOptions::default())]);
+    let partial = vec![circle(300.0, 0.0)[0][..40].to_vec()];
+
+    assert_eq!(
+        block_on(spell_compare_prefix(&partial, 1, &book)).err(),
+        Some(RecognizeError::EmptyCorpus)
+    );
+}
+
+#[test]
+fn only_timed_spells_care_how_fast_they_are_drawn() {
+    let timed = SpellOptions {
+        timed: true,
+        ..SpellOptions::default()
+    };
+    let mut book = SpellBook::new(Backend::Rubine);
+
+    // every example drawn a point every 5ms.
+    for (examples, options) in [
+        (&circles()[..], SpellOptions::default()),
+        (&lines()[..], SpellOptions::default()),
+        (&zigzags()[..], timed),
+    ] {
+        let mut id = None;
+
+        for example in examples.iter() {
+            let timing = steady(example, 5, 0);
+            id = Some(block_on(book.learn(id, options, example.clone(), timing, 1)).unwrap());
+        }
+    }
+
+    // drawn 20 times slower.
+    let cast = |spell: &Spell| block_on(spell_compare(spell, &steady(spell, 100, 0), 1, &book));
+    let line = cast(&horizontal_line()).unwrap();
+    let zigzag = cast(&zigzag()).unwrap();
+
+    assert_eq!(line.spell, 1);
+    assert!(is_cast(&book, &line), "{line:?}");
+    assert!(!is_cast(&book, &zigzag), "{zigzag:?}");
+}
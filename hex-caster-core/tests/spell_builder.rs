@@ -0,0 +1,137 @@
+//! the mock time driver is shared by the whole test binary, so everything that advances it lives
+//! in the one test.
+
+use embassy_time::{Duration, MockDriver};
+use hex_caster_core::{
+    spell_caster::{PREVIEW_EVERY, STROKE_GAP, SpellBuilder},
+    tap::{LONG_PRESS, Tap},
+    touch::{REPORT_SIZE, TouchReport},
+};
+
+fn draw(builder: &mut SpellBuilder, points: &[(u16, u16)]) {
+    for point in points.iter() {
+        builder.step(*point);
+    }
+
+    builder.step((0, 0));
+}
+
+#[test]
+fn strokes_taps_and_fingers_are_tracked_over_time() {
+    let driver = MockDriver::get();
+    let mut builder = SpellBuilder::default();
+    driver.advance(Duration::from_secs(1));
+
+    assert!(!builder.should_cast());
+
+    // the first half of an "X", drawn a point every 10ms.
+    for point in [(100, 100), (200, 200), (200, 200), (300, 300)] {
+        builder.step(point);
+        driver.advance(Duration::from_millis(10));
+    }
+
+    builder.step((0, 0));
+    assert!(!builder.should_cast());
+
+    driver.advance(STROKE_GAP / 2);
+    assert!(!builder.should_cast());
+
+    // touched down again in time, so this is the same spell.
+    draw(&mut builder, &[(300, 100), (200, 200), (100, 300)]);
+    driver.advance(STROKE_GAP);
+    assert!(builder.should_cast());
+
+    // repeated points are dropped.
+    assert_eq!(
+        builder.build(),
+        vec![
+            vec![(100, 100), (200, 200), (300, 300)],
+            vec![(300, 100), (200, 200), (100, 300)],
+        ]
+    );
+
+    // the second stroke is drawn at once, after the gap.
+    let ms = Duration::from_millis;
+    let gap = ms(40) + STROKE_GAP / 2;
+    assert_eq!(
+        builder.timing(),
+        vec![vec![ms(0), ms(10), ms(30)], vec![gap, gap, gap]]
+    );
+
+    assert_eq!(builder.tap(), None);
+
+    builder.reset();
+    assert!(!builder.should_cast());
+    assert!(builder.build().is_empty());
+    assert!(builder.timing().is_empty());
+
+    // two quick touches.
+    for _ in 0..2 {
+        builder.step((400, 300));
+        driver.advance(Duration::from_millis(50));
+        builder.step((0, 0));
+        driver.advance(Duration::from_millis(100));
+    }
+
+    driver.advance(STROKE_GAP);
+    assert!(builder.should_cast());
+    assert_eq!(builder.tap(), Some(Tap::Double));
+    builder.reset();
+
+    // held still, but jittering.
+    builder.step((400, 300));
+    driver.advance(LONG_PRESS / 2);
+    builder.step((403, 302));
+    driver.advance(LONG_PRESS / 2);
+    builder.step((0, 0));
+    driver.advance(STROKE_GAP);
+    assert_eq!(builder.tap(), Some(Tap::LongPress));
+    builder.reset();
+
+    // a two finger swipe, the second finger lifting first.
+    for (x, fingers) in [(100, 2), (200, 2), (300, 1), (400, 1)] {
+        let mut report = [0; REPORT_SIZE];
+        report[2] = 1;
+        report[5..7].copy_from_slice(&u16::to_le_bytes(x));
+        report[7..9].copy_from_slice(&u16::to_le_bytes(100));
+        report[35] = fingers;
+        builder.step_report(&TouchReport::parse(&report).unwrap());
+    }
+
+    builder.step((0, 0));
+    driver.advance(STROKE_GAP);
+    assert_eq!(builder.fingers(), 2);
+    assert_eq!(
+        builder.build(),
+        vec![vec![(100, 100), (200, 100), (300, 100), (400, 100)]]
+    );
+
+    builder.reset();
+    assert_eq!(builder.fingers(), 1);
+}
+
+#[test]
+fn unfinished_first_strokes_are_previewed() {
+    let mut builder = SpellBuilder::default();
+    let mut previews = 0;
+
+    for x in 1..=2 * PREVIEW_EVERY as u16 + 5 {
+        builder.step((x, 100));
+
+        if let Some(preview) = builder.preview() {
+            previews += 1;
+            assert_eq!(preview[0].len(), previews * PREVIEW_EVERY);
+        }
+    }
+
+    assert_eq!(previews, 2);
+
+    // nothing once lifted, or while drawing a second stroke.
+    builder.step((0, 0));
+    assert_eq!(builder.preview(), None);
+
+    for y in 1..=2 * PREVIEW_EVERY as u16 {
+        builder.step((100, y));
+        assert_eq!(builder.preview(), None);
+    }
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::*;
+use hex_caster_core::swipe::Swipe;
+
+#[test]
+fn straight_strokes_are_swipes() {
+    let c = CENTER;
+    let directions = [
+        ((c.0 + 400.0, c.1), Swipe::Right),
+        ((c.0 + 300.0, c.1 + 300.0), Swipe::DownRight),
+        ((c.0, c.1 + 400.0), Swipe::Down),
+        ((c.0 - 300.0, c.1 + 300.0), Swipe::DownLeft),
+        ((c.0 - 400.0, c.1), Swipe::Left),
+        ((c.0 - 300.0, c.1 - 300.0), Swipe::UpLeft),
+        ((c.0, c.1 - 400.0), Swipe::Up),
+        ((c.0 + 300.0, c.1 - 300.0), Swipe::UpRight),
+    ];
+
+    for (end, swipe) in directions {
+        assert_eq!(Swipe::classify(&vec![line(c, end, 20)]), Some(swipe));
+    }
+
+    // a little off straight still counts.
+    assert_eq!(
+        Swipe::classify(&vec![line(c, (c.0 + 400.0, c.1 - 80.0), 20)]),
+        Some(Swipe::Right)
+    );
+}
+
+#[test]
+fn other_strokes_are_not_swipes() {
+    assert_eq!(Swipe::classify(&circle(300.0, 0.0)), None);
+    assert_eq!(Swipe::classify(&chevron_up()), None);
+    assert_eq!(Swipe::classify(&equals()), None);
+    // too short.
+    assert_eq!(
+        Swipe::classify(&vec![line(CENTER, (CENTER.0 + 50.0, CENTER.1), 10)]),
+        None
+    );
+    assert_eq!(Swipe::classify(&vec![]), None);
+}
+
+#[test]
+fn swipes_parse() {
+    for swipe in [
+        Swipe::Right,
+        Swipe::DownRight,
+        Swipe::Down,
+        Swipe::DownLeft,
+        Swipe::Left,
+        Swipe::UpLeft,
+        Swipe::Up,
+        Swipe::UpRight,
+    ] {
+        assert_eq!(swipe.to_string().parse(), Ok(swipe));
+    }
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::*;
+use embassy_time::Duration;
+use hex_caster_core::tap::{LONG_PRESS, TAP_TIME, Tap};
+
+const QUICK: Duration = Duration::from_millis(80);
+
+#[test]
+fn taps_are_counted() {
+    let touch = vec![(400, 300), (402, 301)];
+
+    assert_eq!(
+        Tap::classify(&vec![touch.clone()], &[QUICK]),
+        Some(Tap::Single)
+    );
+    assert_eq!(
+        Tap::classify(&vec![touch.clone(); 2], &[QUICK; 2]),
+        Some(Tap::Double)
+    );
+    assert_eq!(
+        Tap::classify(&vec![touch.clone(); 3], &[QUICK; 3]),
+        Some(Tap::Triple)
+    );
+    assert_eq!(Tap::classify(&vec![touch; 4], &[QUICK; 4]), None);
+}
+
+#[test]
+fn long_press_is_a_held_touch() {
+    // jitter of a finger held still.
+    let held = vec![(400, 300), (405, 298), (398, 304), (401, 309), (396, 300)];
+
+    assert_eq!(
+        Tap::classify(&vec![held.clone()], &[LONG_PRESS]),
+        Some(Tap::LongPress)
+    );
+    // neither a tap nor a long press.
+    assert_eq!(
+        Tap::classify(&vec![held.clone()], &[TAP_TIME + QUICK]),
+        None
+    );
+    assert_eq!(
+        Tap::classify(&vec![held.clone(); 2], &[LONG_PRESS, QUICK]),
+        None
+    );
+}
+
+#[test]
+fn strokes_are_not_taps() {
+    assert_eq!(Tap::classify(&circle(100.0, 0.0), &[QUICK]), None);
+    assert_eq!(
+        Tap::classify(
+            &vec![vec![(400, 300)], line((0.0, 0.0), (300.0, 0.0), 20)],
+            &[QUICK; 2]
+        ),
+        None
+    );
+    assert_eq!(Tap::classify(&vec![], &[]), None);
+}
+
+#[test]
+fn taps_parse() {
+    for tap in [Tap::Single, Tap::Double, Tap::Triple, Tap::LongPress] {
+        assert_eq!(tap.to_string().parse(), Ok(tap));
+    }
+
+    assert!("quadruple-tap".parse::<Tap>().is_err());
+}
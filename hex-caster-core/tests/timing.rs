@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use embassy_futures::block_on;
+use embassy_time::Duration;
+use hex_caster_core::{
+    Spell, Timing,
+    spell_compare::{Backend, SpellBook, SpellOptions, spell_compare},
+    timing::{PROFILE_SAMPLES, Tempo},
+};
+
+/// the one stroke spell drawn in `ms` milliseconds, slowly at first & then faster & faster.
+fn speeding_up(spell: &Spell, ms: u64) -> Timing {
+    let n = spell[0].len() as f32 - 1.0;
+
+    vec![
+        (0..spell[0].len())
+            .map(|i| Duration::from_millis((ms as f32 * (i as f32 / n).sqrt()) as u64))
+            .collect(),
+    ]
+}
+
+#[test]
+fn tempos_compare_duration_and_speed_profile() {
+    let spell = horizontal_line();
+    let steady_tempo = Tempo::of(&spell, &steady(&spell, 10, 0)).unwrap();
+
+    assert!((steady_tempo.duration - 0.39).abs() < 1e-4);
+    assert!(steady_tempo.profile.iter().all(|s| (s - 1.0).abs() < 0.01));
+    assert_eq!(steady_tempo.profile.len(), PROFILE_SAMPLES);
+
+    let speeding_tempo = Tempo::of(&spell, &speeding_up(&spell, 390)).unwrap();
+    assert!(speeding_tempo.profile[0] < speeding_tempo.profile[PROFILE_SAMPLES - 1]);
+    assert!(speeding_tempo.max_speed > steady_tempo.max_speed);
+
+    let a_bit_slower = Tempo::of(&spell, &steady(&spell, 13, 0)).unwrap();
+    let much_slower = Tempo::of(&spell, &steady(&spell, 30, 0)).unwrap();
+
+    assert!(steady_tempo.similarity(&a_bit_slower) > 0.99);
+    assert!(steady_tempo.similarity(&speeding_tempo) < steady_tempo.similarity(&a_bit_slower));
+    assert_eq!(steady_tempo.similarity(&much_slower), 0.0);
+
+    // untimed, or timed for another spell.
+    assert_eq!(Tempo::of(&spell, &Vec::new()), None);
+    assert_eq!(Tempo::of(&cross(), &steady(&spell, 10, 0)), None);
+}
+
+#[test]
+fn timed_spells_tell_a_flick_from_a_drag() {
+    let line = horizontal_line();
+    let flick = steady(&line, 5, 0);
+    let drag = speeding_up(&line, 1_200);
+    let timed = SpellOptions {
+        timed: true,
+        ..SpellOptions::default()
+    };
+
+    for backend in [Backend::DollarOne, Backend::DollarP, Backend::Rubine] {
+        let mut book = SpellBook::new(backend);
+        block_on(book.learn(None, timed, line.clone(), flick.clone(), 1)).unwrap();
+        block_on(book.learn(None, timed, line.clone(), drag.clone(), 1)).unwrap();
+
+        for (timing, id) in [(steady(&line, 6, 0), 0), (speeding_up(&line, 1_000), 1)] {
+            let recognition = block_on(spell_compare(&line, &timing, 1, &book)).unwrap();
+
+            assert_eq!(recognition.spell, id, "{backend:?} {recognition:?}");
+        }
+
+        // without the time it was drawn in, a timed spell's templates can not be matched.
+        if backend != Backend::Rubine {
+            let recognition = block_on(spell_compare(&line, &Vec::new(), 1, &book)).unwrap();
+            assert_eq!(recognition.score, 0.0, "{backend:?}");
+        }
+    }
+}
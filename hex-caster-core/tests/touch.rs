@@ -0,0 +1,48 @@
+use hex_caster_core::touch::{REPORT_SIZE, TouchReport};
+
+/// a touch report with a contact at each of `points`.
+fn report(points: &[(u16, u16)]) -> [u8; REPORT_SIZE] {
+    let mut report = [0; REPORT_SIZE];
+    report[0] = REPORT_SIZE as u8;
+    report[2] = 1;
+
+    for (i, (x, y)) in points.iter().enumerate() {
+        let contact = 3 + i * 6;
+        report[contact] = 0x03;
+        report[contact + 1] = i as u8;
+        report[contact + 2..contact + 4].copy_from_slice(&x.to_le_bytes());
+        report[contact + 4..contact + 6].copy_from_slice(&y.to_le_bytes());
+    }
+
+    report[35] = points.len() as u8;
+    report
+}
+
+#[test]
+fn contacts_are_parsed() {
+    let report = TouchReport::parse(&report(&[(300, 200), (700, 650)])).unwrap();
+    let contacts = report.contacts();
+
+    assert_eq!(contacts.len(), 2);
+    assert_eq!((contacts[0].id, contacts[0].point), (0, (300, 200)));
+    assert_eq!((contacts[1].id, contacts[1].point), (1, (700, 650)));
+    assert_eq!(contacts[1].flags, 0x03);
+    assert_eq!(report.point(), (300, 200));
+}
+
+#[test]
+fn lifted_reports_have_no_contacts() {
+    let report = TouchReport::parse(&report(&[])).unwrap();
+
+    assert!(report.contacts().is_empty());
+    assert_eq!(report.point(), (0, 0));
+}
+
+#[test]
+fn other_reports_are_ignored() {
+    let mut other = report(&[(300, 200)]);
+    other[2] = 2;
+
+    assert_eq!(TouchReport::parse(&other), None);
+    assert_eq!(TouchReport::parse(&report(&[(300, 200)])[..9]), None);
+}
@@ -8,22 +8,19 @@
 
 #![no_std]
 #![no_main]
-#![feature(more_float_constants)]
 
-#[macro_use]
 extern crate alloc;
 
-use crate::spell_caster::SpellBuilder;
-use crate::spell_compare::process_stroke;
 use alloc::vec::Vec;
 use core::ptr::addr_of_mut;
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use embassy_executor::{Executor, Spawner};
+use embassy_futures::select::{Either, Either3, select, select3};
 use embassy_rp::gpio::{Input, Pull};
 use embassy_rp::peripherals::PIN_3;
 use embassy_rp::{
     Peri, bind_interrupts, gpio,
-    i2c::InterruptHandler as I2cIrqHandler,
+    i2c::{Async, I2c, InterruptHandler as I2cIrqHandler},
     multicore::{Stack, spawn_core1},
     peripherals::{I2C0, PIN_4, PIN_5, USB},
     usb::{Driver, InterruptHandler as UsbIrqHandler},
@@ -31,8 +28,9 @@ use embassy_rp::{
 use embassy_sync::{
     blocking_mutex::raw::CriticalSectionRawMutex,
     channel::{Channel, Receiver, Sender},
+    signal::Signal,
 };
-use embassy_time::{Duration, Timer};
+use embassy_time::{Duration, Instant, Timer};
 use embassy_usb::{
     class::{
         cdc_acm::{CdcAcmClass, State},
@@ -44,15 +42,23 @@ use embassy_usb::{
 use embassy_usb_logger::ReceiverHandler;
 use embedded_alloc::LlffHeap as Heap;
 use gpio::{Level, Output};
+use hex_caster_core::{
+    Spell, SpellId, Timing,
+    binding::{Bindings, DEFAULT_SHORTCUT, Shortcut, Trigger},
+    combo::{Combos, Dispatch},
+    pad::{self, PadRegion, SizeClass},
+    spell_caster::{HOLD_TIME, SpellBuilder},
+    spell_compare::{self, Backend, DEFAULT_MARGIN, Recognition, SpellBook, SpellOptions},
+    swipe::Swipe,
+    tap::Tap,
+    touch::{REPORT_SIZE, TouchReport},
+};
 use log::*;
 use static_cell::StaticCell;
 use usbd_hid::descriptor::{KeyboardReport, SerializedDescriptor};
 
 use {defmt_rtt as _, panic_probe as _};
 
-pub mod spell_caster;
-pub mod spell_compare;
-
 // Program metadata for `picotool info`.
 // This isn't needed, but it's recomended to have these minimal entries.
 #[unsafe(link_section = ".bi_entries")]
@@ -81,24 +87,88 @@ bind_interrupts!(struct Irqs {
     I2C0_IRQ => I2cIrqHandler<I2C0>;
 });
 
-pub type Point = (u16, u16);
-pub type SpellId = usize;
-pub type Spell = Vec<Point>;
 pub type KbdShortcut = Vec<KbdEvent>;
 
 const ADDR: u8 = 0x2c;
-// the full report is 37 bytes long but we don't need that much data & the data is generated on
-// i2c reads so might as well save some time & only read what we need;
-const USB_HID_REPORT_SIZE: usize = 9;
+/// the register the touch-pad's HID descriptor is read from, as HID over I2C names it.
+const HID_DESCRIPTOR_REGISTER: u16 = 0x0020;
+/// how long the HID descriptor is, in HID over I2C version 1.00.
+const HID_DESCRIPTOR_SIZE: usize = 30;
+/// how many of the best matches are logged when a cast fails.
+const CANDIDATES: usize = 3;
+/// the backend used at boot, until changed with `/backend`.
+const BACKEND: Backend = Backend::DollarOne;
 
 #[global_allocator]
 static HEAP: Heap = Heap::empty();
 const HEAP_SIZE: usize = 128 * 1024;
 
-// static COMMAND_CHANNEL: Channel<CriticalSectionRawMutex, String, 4> = Channel::new();
-static SPELL_CHANNEL: Channel<CriticalSectionRawMutex, Spell, 4> = Channel::new();
+static COMMAND_CHANNEL: Channel<CriticalSectionRawMutex, Command, 4> = Channel::new();
+static SPELL_CHANNEL: Channel<CriticalSectionRawMutex, Gesture, 4> = Channel::new();
 static KBD_CHANNEL: Channel<CriticalSectionRawMutex, KeyboardReport, 4> = Channel::new();
-static LEARNING: AtomicBool = AtomicBool::new(true);
+/// how long the finger has to rest at the end of a spell to hold it, in ms. 0 never holds. read by
+/// the trackpad task, which has no command channel.
+static HOLD_MS: AtomicU32 = AtomicU32::new(HOLD_TIME.as_millis() as u32);
+/// true when a held spell is bound to anything, alone or in a combo. until then the trackpad task
+/// never holds spells, so the LED does not flash for holds that would do nothing.
+static HOLD_BOUND: AtomicBool = AtomicBool::new(false);
+/// signalled when a resting finger has held long enough, to flash the LED before it lifts.
+static HOLD_REACHED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// serial commands that change the state of the `spell_caster` task.
+pub enum Command {
+    /// start learning a new spell, every following stroke is an example of it.
+    Learn(SpellOptions),
+    Cast,
+    /// overrides the acceptance threshold derived from a spell's examples.
+    SetThreshold(SpellId, f32),
+    /// sets how far ahead of the runner up a match must be to be cast.
+    SetMargin(f32),
+    /// retrains the learned spells on a different recognizer.
+    SetBackend(Backend),
+    /// makes every spell match when mirrored, not just those learned with `mirror`.
+    SetMirror(bool),
+    Forget(SpellId),
+    /// makes a spell or a tap run a different shortcut.
+    Bind(Trigger, Shortcut),
+    /// stops a tap running its shortcut, or returns a spell to `DEFAULT_SHORTCUT`.
+    Unbind(Trigger),
+    /// makes a sequence of spells, taps or swipes run a shortcut of its own.
+    Combo(Vec<Trigger>, Shortcut),
+    Uncombo(Vec<Trigger>),
+    /// sets how long to wait for the next trigger of a combo.
+    SetComboTimeout(Duration),
+    SetEarly(Early),
+}
+
+/// what the `spell_caster` task does with spells that are still being drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Early {
+    Off,
+    /// logs the spell it looks like over serial.
+    Preview,
+    /// also casts it, once the recognition is decisive, without waiting for the finger to lift.
+    Cast,
+}
+
+/// what the trackpad task saw, sent to the `spell_caster` task.
+pub enum Gesture {
+    /// a spell, when each of its points was drawn, how many fingers drew it & whether it was
+    /// held at the end.
+    Spell(Spell, Timing, u8, bool),
+    /// the first stroke of a spell, still being drawn.
+    Partial(Spell, u8),
+    Tap(Tap),
+}
+
+enum CasterMode {
+    /// strokes are examples of `spell`, which is created by the first one.
+    Learning {
+        options: SpellOptions,
+        spell: Option<SpellId>,
+    },
+    Casting,
+}
 
 pub enum KbdEvent {
     Press { scan_code: u8, is_mod: bool },
@@ -121,20 +191,136 @@ impl ReceiverHandler for CmdHandler {
         match core::str::from_utf8(data) {
             Ok(cmd) => {
                 info!("recv a command {cmd}");
-                // let mut buf = [0u8; 256];
-                // COMMAND_CHANNEL.send(cmd.to_string()).await;
-
                 if cmd.starts_with("/greet ") {
                     let name = &cmd[7..cmd.len()];
                     info!("Hello, {name}!");
                 } else if cmd.starts_with("/learn") {
-                    LEARNING.store(true, Ordering::Relaxed);
+                    let has_flag = |flag| cmd.split_whitespace().any(|arg| arg == flag);
+                    let options = SpellOptions {
+                        rotation_sensitive: !has_flag("any-angle"),
+                        direction_sensitive: !has_flag("any-direction"),
+                        mirror_invariant: has_flag("mirror"),
+                        position_aware: has_flag("here"),
+                        size_aware: has_flag("sized"),
+                        time_warped: has_flag("warp"),
+                        timed: has_flag("timed"),
+                    };
+                    COMMAND_CHANNEL.send(Command::Learn(options)).await;
                     // Timer::after(Duration::from_millis(3000)).await;
-                    info!("entering learn mode");
+                    info!("learning a new spell ({options:?})");
                 } else if cmd.starts_with("/cast") {
-                    LEARNING.store(false, Ordering::Relaxed);
+                    COMMAND_CHANNEL.send(Command::Cast).await;
                     // Timer::after(Duration::from_millis(3000)).await;
                     info!("entering casting mode");
+                } else if cmd.starts_with("/threshold ") {
+                    let mut args = cmd[11..cmd.len()].split_whitespace();
+
+                    match (
+                        args.next().and_then(|id| id.parse().ok()),
+                        args.next().and_then(parse_score),
+                    ) {
+                        (Some(id), Some(threshold)) => {
+                            COMMAND_CHANNEL
+                                .send(Command::SetThreshold(id, threshold))
+                                .await;
+                        }
+                        _ => error!("usage: /threshold <spell id> <threshold, from 0 to 1>"),
+                    }
+                } else if cmd.starts_with("/margin ") {
+                    match parse_score(cmd[8..cmd.len()].trim()) {
+                        Some(margin) => COMMAND_CHANNEL.send(Command::SetMargin(margin)).await,
+                        None => error!("usage: /margin <margin, from 0 to 1>"),
+                    }
+                } else if cmd.starts_with("/backend ") {
+                    match cmd[9..cmd.len()].trim().parse() {
+                        Ok(backend) => COMMAND_CHANNEL.send(Command::SetBackend(backend)).await,
+                        Err(_) => {
+                            error!("usage: /backend <$1 | protractor | $p | shortstraw | rubine>")
+                        }
+                    }
+                } else if cmd.starts_with("/mirror ") {
+                    match cmd[8..cmd.len()].trim() {
+                        "on" => COMMAND_CHANNEL.send(Command::SetMirror(true)).await,
+                        "off" => COMMAND_CHANNEL.send(Command::SetMirror(false)).await,
+                        _ => error!("usage: /mirror <on | off>"),
+                    }
+                } else if cmd.starts_with("/bind ") {
+                    let mut args = cmd[6..cmd.len()].split_whitespace();
+
+                    match (
+                        args.next().and_then(|trigger| trigger.parse().ok()),
+                        args.next().and_then(|shortcut| shortcut.parse().ok()),
+                    ) {
+                        (Some(trigger), Some(shortcut)) => {
+                            COMMAND_CHANNEL.send(Command::Bind(trigger, shortcut)).await;
+                        }
+                        _ => error!(
+                            "usage: /bind <spell id | tap | double-tap | triple-tap | long-press | [n-finger-]swipe-<direction>> <keys, like ctrl+alt+t>"
+                        ),
+                    }
+                } else if cmd.starts_with("/unbind ") {
+                    match cmd[8..cmd.len()].trim().parse() {
+                        Ok(trigger) => COMMAND_CHANNEL.send(Command::Unbind(trigger)).await,
+                        Err(_) => error!(
+                            "usage: /unbind <spell id | tap | double-tap | triple-tap | long-press | [n-finger-]swipe-<direction>>"
+                        ),
+                    }
+                } else if cmd.starts_with("/combo ") {
+                    let mut args = cmd[7..cmd.len()].split_whitespace();
+
+                    match (
+                        args.next().and_then(parse_sequence),
+                        args.next().and_then(|shortcut| shortcut.parse().ok()),
+                    ) {
+                        (Some(sequence), Some(shortcut)) => {
+                            COMMAND_CHANNEL
+                                .send(Command::Combo(sequence, shortcut))
+                                .await;
+                        }
+                        _ => error!(
+                            "usage: /combo <at least two triggers, like 0,swipe-down> <keys, like ctrl+alt+t>"
+                        ),
+                    }
+                } else if cmd.starts_with("/uncombo ") {
+                    match parse_sequence(cmd[9..cmd.len()].trim()) {
+                        Some(sequence) => COMMAND_CHANNEL.send(Command::Uncombo(sequence)).await,
+                        None => {
+                            error!("usage: /uncombo <at least two triggers, like 0,swipe-down>")
+                        }
+                    }
+                } else if cmd.starts_with("/combo-timeout ") {
+                    match cmd[15..cmd.len()].trim().parse() {
+                        Ok(ms) => {
+                            COMMAND_CHANNEL
+                                .send(Command::SetComboTimeout(Duration::from_millis(ms)))
+                                .await
+                        }
+                        Err(_) => error!("usage: /combo-timeout <milliseconds>"),
+                    }
+                } else if cmd.starts_with("/hold ") {
+                    match cmd[6..cmd.len()].trim().parse() {
+                        Ok(ms) => {
+                            HOLD_MS.store(ms, Ordering::Relaxed);
+                            info!("spells are held after resting {ms} ms (0 is never)");
+                        }
+                        Err(_) => error!("usage: /hold <milliseconds, or 0 to never hold>"),
+                    }
+                } else if cmd.starts_with("/early ") {
+                    match cmd[7..cmd.len()].trim() {
+                        "off" => COMMAND_CHANNEL.send(Command::SetEarly(Early::Off)).await,
+                        "preview" => {
+                            COMMAND_CHANNEL
+                                .send(Command::SetEarly(Early::Preview))
+                                .await
+                        }
+                        "cast" => COMMAND_CHANNEL.send(Command::SetEarly(Early::Cast)).await,
+                        _ => error!("usage: /early <off | preview | cast>"),
+                    }
+                } else if cmd.starts_with("/forget ") {
+                    match cmd[8..cmd.len()].trim().parse() {
+                        Ok(id) => COMMAND_CHANNEL.send(Command::Forget(id)).await,
+                        Err(_) => error!("usage: /forget <spell id>"),
+                    }
                 } else if cmd.starts_with("/") {
                     error!("unknown command!");
                 }
@@ -144,6 +330,22 @@ impl ReceiverHandler for CmdHandler {
     }
 }
 
+/// parses a comma separated combo, like `0,swipe-down,double-tap`.
+fn parse_sequence(arg: &str) -> Option<Vec<Trigger>> {
+    let sequence = arg
+        .split(',')
+        .map(|trigger| trigger.parse().ok())
+        .collect::<Option<Vec<Trigger>>>()?;
+
+    (sequence.len() >= 2).then_some(sequence)
+}
+
+/// parses a threshold or margin, which are scores from 0 to 1. NaN would pass or fail every
+/// comparison, so it is refused with the rest.
+fn parse_score(arg: &str) -> Option<f32> {
+    arg.parse().ok().filter(|score| (0.0..=1.0).contains(score))
+}
+
 struct HidRequestHandler {}
 
 impl RequestHandler for HidRequestHandler {
@@ -257,8 +459,8 @@ async fn main(spawner: Spawner) {
                 spawner
                     .spawn(spell_caster(
                         SPELL_CHANNEL.receiver(),
+                        COMMAND_CHANNEL.receiver(),
                         KBD_CHANNEL.sender(),
-                        // learning,
                     ))
                     .unwrap()
             })
@@ -279,35 +481,197 @@ async fn main(spawner: Spawner) {
 
 #[embassy_executor::task]
 async fn spell_caster(
-    spell_cast_msg: Receiver<'static, CriticalSectionRawMutex, Spell, 4>,
+    spell_cast_msg: Receiver<'static, CriticalSectionRawMutex, Gesture, 4>,
+    commands: Receiver<'static, CriticalSectionRawMutex, Command, 4>,
     kbd_sender: Sender<'static, CriticalSectionRawMutex, KeyboardReport, 4>,
-    // learning: Arc<AtomicBool>,
 ) {
-    // will be a Vec<Vec<NormedSpell>> with each Vec<NormedSpell> representing a collection of
-    // examples of a spells.
-    let mut spells = Vec::new();
+    let mut spell_book = SpellBook::new(BACKEND);
+    let mut mode = CasterMode::Learning {
+        options: SpellOptions::default(),
+        spell: None,
+    };
+    let mut margin = DEFAULT_MARGIN;
+    let mut bindings = Bindings::default();
+    let mut combos = Combos::default();
+    let mut early = Early::Off;
+    // set when the spell being drawn was already cast from a preview.
+    let mut cast_early = false;
 
     loop {
         warn!("awaiting new spell");
-        let spell_symbol = spell_cast_msg.receive().await;
-
-        if spell_symbol.len() < 5 {
-            warn!("Gesture too short, ignoring");
-            continue;
-        }
+        let (spell_symbol, timing, fingers, held) = match select3(
+            spell_cast_msg.receive(),
+            commands.receive(),
+            Timer::at(combos.deadline().unwrap_or(Instant::MAX)),
+        )
+        .await
+        {
+            Either3::First(Gesture::Spell(spell_symbol, timing, fingers, held)) => {
+                (spell_symbol, timing, fingers, held)
+            }
+            Either3::First(Gesture::Partial(partial, fingers)) => {
+                if early != Early::Off
+                    && !cast_early
+                    && matches!(mode, CasterMode::Casting)
+                    && let Some(spell) = preview(&partial, fingers, &spell_book, early).await
+                {
+                    // casting early would leave no chance to hold the spell.
+                    if HOLD_MS.load(Ordering::Relaxed) != 0
+                        && combos.spell_trigger(spell, true, &bindings) == Trigger::Held(spell)
+                    {
+                        info!("not casting spell {spell} early, it might still be held");
+                    } else {
+                        warn!("casting spell {spell} early");
+                        let dispatches = combos.push(Trigger::Spell(spell), Instant::now());
+                        dispatch(&kbd_sender, &bindings, dispatches).await;
+                        cast_early = true;
+                    }
+                }
+                continue;
+            }
+            Either3::First(Gesture::Tap(tap)) => {
+                cast_early = false;
+
+                if let CasterMode::Learning { .. } = mode {
+                    warn!("{tap} is not a spell, bind it with /bind instead");
+                } else {
+                    let dispatches = combos.push(Trigger::Tap(tap), Instant::now());
+                    dispatch(&kbd_sender, &bindings, dispatches).await;
+                }
+                continue;
+            }
+            Either3::Third(()) => {
+                let dispatches = combos.poll(Instant::now());
+                dispatch(&kbd_sender, &bindings, dispatches).await;
+                continue;
+            }
+            Either3::Second(Command::Learn(options)) => {
+                mode = CasterMode::Learning {
+                    options,
+                    spell: None,
+                };
+                continue;
+            }
+            Either3::Second(Command::Cast) => {
+                mode = CasterMode::Casting;
+                continue;
+            }
+            Either3::Second(Command::SetThreshold(id, threshold)) => {
+                match spell_book.set_threshold(id, threshold) {
+                    Some(old) => info!("spell {id} threshold changed from {old} to {threshold}"),
+                    None => error!("no spell with id {id}"),
+                }
+                continue;
+            }
+            Either3::Second(Command::SetMargin(new_margin)) => {
+                info!("ambiguity margin changed from {margin} to {new_margin}");
+                margin = new_margin;
+                continue;
+            }
+            Either3::Second(Command::SetBackend(backend)) => {
+                spell_book.set_backend(backend).await;
+                info!("recognizing spells with {backend:?}");
+                continue;
+            }
+            Either3::Second(Command::SetMirror(mirror_invariant)) => {
+                spell_book.set_mirror_invariant(mirror_invariant).await;
+                info!("mirrored spells are matched: {mirror_invariant}");
+                continue;
+            }
+            Either3::Second(Command::Bind(trigger, shortcut)) => {
+                bindings.bind(trigger, shortcut);
+                HOLD_BOUND.store(bindings.holds() || combos.holds(), Ordering::Relaxed);
+                info!("{trigger:?} now runs {shortcut:?}");
+                continue;
+            }
+            Either3::Second(Command::Unbind(trigger)) => {
+                bindings.unbind(trigger);
+                HOLD_BOUND.store(bindings.holds() || combos.holds(), Ordering::Relaxed);
+                info!("unbound {trigger:?}");
+                continue;
+            }
+            Either3::Second(Command::Combo(sequence, shortcut)) => {
+                info!("{sequence:?} now runs {shortcut:?}");
+                let dispatches = combos.bind(sequence, shortcut);
+                HOLD_BOUND.store(bindings.holds() || combos.holds(), Ordering::Relaxed);
+                dispatch(&kbd_sender, &bindings, dispatches).await;
+                continue;
+            }
+            Either3::Second(Command::Uncombo(sequence)) => {
+                let dispatches = combos.unbind(&sequence);
+                HOLD_BOUND.store(bindings.holds() || combos.holds(), Ordering::Relaxed);
+                info!("unbound combo {sequence:?}");
+                dispatch(&kbd_sender, &bindings, dispatches).await;
+                continue;
+            }
+            Either3::Second(Command::SetComboTimeout(timeout)) => {
+                info!(
+                    "combo timeout changed from {} to {} ms",
+                    combos.timeout.as_millis(),
+                    timeout.as_millis()
+                );
+                combos.timeout = timeout;
+                continue;
+            }
+            Either3::Second(Command::SetEarly(new_early)) => {
+                info!("unfinished spells: {new_early:?}");
+                early = new_early;
+                continue;
+            }
+            Either3::Second(Command::Forget(id)) => {
+                if spell_book.forget(id) {
+                    info!("forgot spell {id}");
+                } else {
+                    error!("no spell with id {id}");
+                }
+                continue;
+            }
+        };
 
         debug!(
-            "spell_caster recieved a spell of length {}",
-            spell_symbol.len()
+            "spell_caster recieved a spell of {} strokes & length {}",
+            spell_symbol.len(),
+            spell_symbol
+                .iter()
+                .map(|stroke| stroke.len())
+                .sum::<usize>()
         );
-        let cast_spell = process_stroke(spell_symbol).await;
-
-        if LEARNING.load(Ordering::Relaxed) {
-            spells.push(cast_spell);
-            info!("learned a new spell! (spell no. {})", spells.len());
 
-            // LEARNING.store(false, Ordering::Relaxed);
+        if let CasterMode::Learning { options, spell } = &mut mode {
+            let (region, size) = (PadRegion::of(&spell_symbol), SizeClass::of(&spell_symbol));
+
+            match spell_book
+                .learn(*spell, *options, spell_symbol, timing, fingers)
+                .await
+            {
+                Ok(id) => {
+                    *spell = Some(id);
+                    info!(
+                        "learned example no. {} of spell {id} (threshold: {}, drawn {size:?} {region:?} with {fingers} fingers)",
+                        spell_book.spells[id].examples.len(),
+                        spell_book.spells[id].threshold
+                    );
+                }
+                Err(e) => warn!("{e}, ignoring"),
+            }
         } else {
+            if cast_early {
+                info!("spell was already cast early");
+                cast_early = false;
+                continue;
+            }
+
+            // built in swipes go first, unless they are unused & so might be learned spells.
+            if let Some(swipe) = Swipe::classify(&spell_symbol)
+                && let trigger = Trigger::Swipe(swipe, fingers)
+                && (bindings.get(trigger).is_some() || combos.uses(trigger))
+            {
+                info!("{fingers} finger {swipe}");
+                let dispatches = combos.push(trigger, Instant::now());
+                dispatch(&kbd_sender, &bindings, dispatches).await;
+                continue;
+            }
+
             info!("comparing spell to corpus");
 
             // if let Some(spell) = spells.get(0) {
@@ -331,33 +695,46 @@ async fn spell_caster(
             //
             // info!("comp_value: {comp_value}");
 
-            let (spell, comp_value) = spell_compare::spell_compare(cast_spell, &spells).await;
-            info!("comp_value: {comp_value}");
-
-            // if comp_value < 0.025 && !comp_value.is_nan() {
-            if comp_value > 0.6 && !comp_value.is_nan() {
-                warn!("running short cut");
-                let report = KeyboardReport {
-                    modifier: 0x08,
-                    leds: 0,
-                    reserved: 0,
-                    keycodes: [0x28, 0, 0, 0, 0, 0],
-                };
-
-                kbd_sender.send(report).await;
-
-                Timer::after(Duration::from_millis(250)).await;
+            let started = Instant::now();
+            let candidates = match spell_compare::spell_compare_top_k(
+                &spell_symbol,
+                &timing,
+                fingers,
+                &spell_book,
+                CANDIDATES,
+            )
+            .await
+            {
+                Ok(candidates) => candidates,
+                Err(e) => {
+                    warn!("{e}, ignoring");
+                    continue;
+                }
+            };
+            debug!("recognized in {} us", started.elapsed().as_micros());
+            let Some(recognition) = Recognition::from_candidates(&candidates) else {
+                continue;
+            };
+            let (spell, comp_value) = (recognition.spell, recognition.score);
+            info!("best match: spell {spell}, comp_value: {comp_value}");
 
-                let report = KeyboardReport {
-                    keycodes: [0, 0, 0, 0, 0, 0],
-                    leds: 0,
-                    modifier: 0,
-                    reserved: 0,
-                };
+            let threshold = spell_book.spells[spell].threshold;
 
-                kbd_sender.send(report).await;
+            // if comp_value < 0.025 && !comp_value.is_nan() {
+            if comp_value <= threshold {
+                warn!("comparison failed, you probably meant one of {candidates:?}");
+            } else if let Some((runner_up, runner_up_value)) = recognition
+                .runner_up
+                .filter(|_| recognition.is_ambiguous(margin))
+            {
+                warn!(
+                    "ambiguous between spell {spell} and {runner_up} ({comp_value} vs {runner_up_value})"
+                );
             } else {
-                warn!("comparison failed");
+                warn!("running short cut");
+                let trigger = combos.spell_trigger(spell, held, &bindings);
+                let dispatches = combos.push(trigger, Instant::now());
+                dispatch(&kbd_sender, &bindings, dispatches).await;
             }
         }
 
@@ -367,18 +744,119 @@ async fn spell_caster(
     }
 }
 
+/// logs what the unfinished spell looks like, returning it if it should be cast early.
+async fn preview(
+    partial: &Spell,
+    fingers: u8,
+    spell_book: &SpellBook,
+    early: Early,
+) -> Option<SpellId> {
+    let recognition = match spell_compare::spell_compare_prefix(partial, fingers, spell_book).await
+    {
+        Ok(recognition) => recognition,
+        Err(e) => {
+            trace!("no preview: {e}");
+            return None;
+        }
+    };
+    let spell = recognition.spell;
+    info!("preview: spell {spell} ({})", recognition.score);
+
+    (early == Early::Cast && recognition.is_decisive(spell_book.spells[spell].threshold))
+        .then_some(spell)
+}
+
+/// runs the shortcuts of completed combos & of triggers that were not part of one.
+async fn dispatch(
+    kbd_sender: &Sender<'static, CriticalSectionRawMutex, KeyboardReport, 4>,
+    bindings: &Bindings,
+    dispatches: Vec<Dispatch>,
+) {
+    for dispatch in dispatches {
+        let shortcut = match dispatch {
+            Dispatch::Combo(shortcut) => {
+                info!("combo complete, running {shortcut:?}");
+                shortcut
+            }
+            Dispatch::Trigger(trigger @ Trigger::Spell(_)) => {
+                bindings.get(trigger).unwrap_or(DEFAULT_SHORTCUT)
+            }
+            // a spell held with nothing bound to the hold is cast as usual.
+            Dispatch::Trigger(trigger @ Trigger::Held(spell)) => match bindings.get(trigger) {
+                Some(shortcut) => {
+                    info!("spell {spell} held, running {shortcut:?}");
+                    shortcut
+                }
+                None => bindings
+                    .get(Trigger::Spell(spell))
+                    .unwrap_or(DEFAULT_SHORTCUT),
+            },
+            Dispatch::Trigger(trigger) => match bindings.get(trigger) {
+                Some(shortcut) => {
+                    info!("{trigger:?}, running {shortcut:?}");
+                    shortcut
+                }
+                None => {
+                    info!("{trigger:?} is not bound to anything");
+                    continue;
+                }
+            },
+        };
+
+        run_shortcut(kbd_sender, shortcut).await;
+    }
+}
+
+/// presses the shortcut's keys, holds them for a moment, then releases them.
+async fn run_shortcut(
+    kbd_sender: &Sender<'static, CriticalSectionRawMutex, KeyboardReport, 4>,
+    shortcut: Shortcut,
+) {
+    let report = KeyboardReport {
+        modifier: shortcut.modifier,
+        leds: 0,
+        reserved: 0,
+        keycodes: shortcut.keycodes,
+    };
+
+    kbd_sender.send(report).await;
+
+    Timer::after(Duration::from_millis(250)).await;
+
+    let report = KeyboardReport {
+        keycodes: [0, 0, 0, 0, 0, 0],
+        leds: 0,
+        modifier: 0,
+        reserved: 0,
+    };
+
+    kbd_sender.send(report).await;
+}
+
 #[embassy_executor::task]
 async fn trackpad_position(
     i2c: Peri<'static, I2C0>,
     sda: Peri<'static, PIN_4>,
     scl: Peri<'static, PIN_5>,
     interupt: Peri<'static, PIN_3>,
-    spell_caster: Sender<'static, CriticalSectionRawMutex, Spell, 4>,
+    spell_caster: Sender<'static, CriticalSectionRawMutex, Gesture, 4>,
 ) {
     info!("starting I2C track pad task");
     let config = embassy_rp::i2c::Config::default();
     let mut bus = embassy_rp::i2c::I2c::new_async(i2c, scl, sda, Irqs, config);
-    let mut result: [u8; USB_HID_REPORT_SIZE] = [0u8; USB_HID_REPORT_SIZE];
+
+    match read_pad_size(&mut bus).await {
+        Some(size) => {
+            info!("the pad reports up to {size:?}");
+            pad::set_pad_size(size);
+        }
+        None => warn!(
+            "could not read the pad's size, assuming {:?}",
+            pad::DEFAULT_PAD_SIZE
+        ),
+    }
+
+    let mut result: [u8; REPORT_SIZE] = [0u8; REPORT_SIZE];
     let mut spell_builder = SpellBuilder::default();
     let mut int_pin = Input::new(interupt, Pull::None);
     // Enable the schmitt trigger to slightly debounce.
@@ -386,47 +864,104 @@ async fn trackpad_position(
 
     loop {
         // int_pin.wait_for_low().await;
+        spell_builder.hold_time = match HOLD_MS.load(Ordering::Relaxed) {
+            0 => None,
+            _ if !HOLD_BOUND.load(Ordering::Relaxed) => None,
+            ms => Some(Duration::from_millis(ms.into())),
+        };
 
         match bus.read_async(ADDR, &mut result).await {
             Ok(_) => {
                 // info!("report type = {}", result[2]);
-                let report_type = result[2];
-
-                if report_type == 1 {
-                    let x = u16::from_le_bytes([result[5], result[6]]);
-                    let y = u16::from_le_bytes([result[7], result[8]]);
-
-                    // if (x + y) != 0 {
-                    //     debug!("({x}, {y})");
+                if let Some(report) = TouchReport::parse(&result) {
+                    // if report.point() != (0, 0) {
+                    //     debug!("{:?}", report.contacts());
                     // }
-                    // debug!("step with point ({x}, {y})");
+                    // debug!("step with point {:?}", report.point());
 
-                    spell_builder.step((x, y));
+                    spell_builder.step_report(&report);
 
-                    if spell_builder.should_cast() {
-                        info!("casting...");
-                        spell_caster.send(spell_builder.build()).await;
-                        spell_builder.reset();
+                    // dropped if the caster is still busy with the last one.
+                    if let Some(partial) = spell_builder.preview() {
+                        let _ = spell_caster
+                            .try_send(Gesture::Partial(partial, spell_builder.fingers()));
                     }
                 }
             }
             Err(e) => error!("could not read from i2c. attempt failed with error: {e:?}"),
         }
+
+        // checked on every read, not just on touch reports, so the gap after the last stroke is
+        // noticed even if the pad stops reporting once the finger is lifted. the same goes for a
+        // finger resting still.
+        if spell_builder.hold_reached() {
+            HOLD_REACHED.signal(());
+        }
+
+        if spell_builder.should_cast() {
+            info!("casting...");
+            let gesture = match spell_builder.tap() {
+                Some(tap) => Gesture::Tap(tap),
+                None => Gesture::Spell(
+                    spell_builder.build(),
+                    spell_builder.timing(),
+                    spell_builder.fingers(),
+                    spell_builder.held(),
+                ),
+            };
+            spell_caster.send(gesture).await;
+            spell_builder.reset();
+        }
     }
 }
 
+/// the largest x & y the touch-pad reports, from the logical maximums in its report descriptor.
+async fn read_pad_size(bus: &mut I2c<'static, I2C0, Async>) -> Option<(u16, u16)> {
+    let mut hid_descriptor = [0u8; HID_DESCRIPTOR_SIZE];
+    bus.write_read_async(
+        ADDR,
+        HID_DESCRIPTOR_REGISTER.to_le_bytes(),
+        &mut hid_descriptor,
+    )
+    .await
+    .ok()?;
+
+    let word = |i: usize| u16::from_le_bytes([hid_descriptor[i], hid_descriptor[i + 1]]);
+    // the descriptor's own length, & the version of HID over I2C it is for.
+    if word(0) as usize != HID_DESCRIPTOR_SIZE || word(2) != 0x0100 {
+        return None;
+    }
+
+    let mut report_descriptor = alloc::vec![0u8; word(4) as usize];
+    bus.write_read_async(ADDR, word(6).to_le_bytes(), &mut report_descriptor)
+        .await
+        .ok()?;
+
+    pad::logical_maximums(&report_descriptor)
+}
+
 #[embassy_executor::task]
 async fn blinky(mut led: Output<'static>) {
     loop {
         led.set_high();
         // trace!("on");
         // debug!("on");
-        Timer::after_millis(250).await;
+        blink_wait(&mut led).await;
 
         led.set_low();
         // trace!("off");
         // debug!("off");
-        Timer::after_millis(250).await;
+        blink_wait(&mut led).await;
+    }
+}
+
+/// waits out half a blink, flickering the LED instead if a spell is held meanwhile.
+async fn blink_wait(led: &mut Output<'static>) {
+    if let Either::Second(()) = select(Timer::after_millis(250), HOLD_REACHED.wait()).await {
+        for _ in 0..6 {
+            led.toggle();
+            Timer::after_millis(40).await;
+        }
     }
 }
 